@@ -0,0 +1,71 @@
+use raytracer::camera::Camera;
+use raytracer::draw::Color;
+use raytracer::light::PointLight;
+use raytracer::material::Material;
+use raytracer::pattern::Checkers;
+use raytracer::shape::{Plane, Shape, Sphere};
+use raytracer::transform;
+use raytracer::tuple::Tuple;
+use raytracer::world::World;
+use std::f64::consts::PI;
+use std::fs;
+use std::sync::Arc;
+
+fn main() {
+    let mut floor = Plane::new();
+    let mut material = Material::new();
+    material.pattern = Some(Arc::new(Checkers::new(
+        Color::new(0.9, 0.9, 0.9),
+        Color::new(0.2, 0.2, 0.2),
+    )));
+    material.specular = 0.0;
+    material.reflective = 0.2;
+    floor.set_material(material);
+
+    let mut mirror = Plane::new();
+    mirror.set_transform(transform::translation(0.0, 0.0, 5.0) * transform::rotation_x(PI / 2.0));
+    let mut material = Material::new();
+    material.color = Color::new(0.1, 0.1, 0.1);
+    material.reflective = 0.9;
+    mirror.set_material(material);
+
+    let mut glass = Sphere::glass();
+    glass.set_transform(transform::translation(-0.5, 1.0, 0.5));
+    let mut material = glass.material().clone();
+    material.color = Color::new(0.1, 0.1, 0.1);
+    material.diffuse = 0.1;
+    material.reflective = 0.9;
+    material.shininess = 300.0;
+    glass.set_material(material);
+    glass.set_casts_shadows(false);
+
+    let mut right = Sphere::new();
+    right.set_transform(transform::translation(1.5, 0.5, -0.5) * transform::scaling(0.5, 0.5, 0.5));
+    let mut material = Material::new();
+    material.color = Color::new(0.5, 1.0, 0.1);
+    material.diffuse = 0.7;
+    material.specular = 0.3;
+    right.set_material(material);
+
+    let mut world = World::new();
+    world.objects = vec![
+        Box::new(floor),
+        Box::new(mirror),
+        Box::new(glass),
+        Box::new(right),
+    ];
+    world.lights.push(PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let mut camera = Camera::new(320, 160, PI / 3.0);
+    camera.set_transform(transform::view_transform(
+        &Tuple::new_point(0.0, 1.5, -5.0),
+        &Tuple::new_point(0.0, 1.0, 0.0),
+        &Tuple::new_vector(0.0, 1.0, 0.0),
+    ));
+
+    let canvas = camera.render(&world);
+    fs::write("./reflections.ppm", canvas.to_ppm()).unwrap();
+}
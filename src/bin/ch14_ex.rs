@@ -0,0 +1,67 @@
+use raytracer::camera::Camera;
+use raytracer::draw::Color;
+use raytracer::light::PointLight;
+use raytracer::shape::{Cylinder, Group, Shape, Sphere};
+use raytracer::transform;
+use raytracer::tuple::Tuple;
+use raytracer::world::World;
+use std::f64::consts::PI;
+use std::fs;
+
+fn hexagon_corner() -> Sphere {
+    let mut corner = Sphere::new();
+    corner.set_transform(
+        transform::translation(0.0, 0.0, -1.0) * transform::scaling(0.25, 0.25, 0.25),
+    );
+    corner
+}
+
+fn hexagon_edge() -> Cylinder {
+    let mut edge = Cylinder::truncated(0.0, 1.0, false);
+    edge.set_transform(
+        transform::translation(0.0, 0.0, -1.0)
+            * transform::rotation_y(-PI / 6.0)
+            * transform::rotation_z(-PI / 2.0)
+            * transform::scaling(0.25, 1.0, 0.25),
+    );
+    edge
+}
+
+fn hexagon_side() -> Group {
+    let mut side = Group::new();
+    side.add_child(Box::new(hexagon_corner()));
+    side.add_child(Box::new(hexagon_edge()));
+    side
+}
+
+fn hexagon() -> Group {
+    let mut hex = Group::new();
+    for n in 0..6 {
+        let mut side = hexagon_side();
+        side.set_transform(transform::rotation_y(n as f64 * PI / 3.0));
+        hex.add_child(Box::new(side));
+    }
+    hex
+}
+
+fn main() {
+    let mut hex = hexagon();
+    hex.set_transform(transform::rotation_x(-PI / 6.0));
+
+    let mut world = World::new();
+    world.objects.push(Box::new(hex));
+    world.lights.push(PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let mut camera = Camera::new(320, 160, PI / 3.0);
+    camera.set_transform(transform::view_transform(
+        &Tuple::new_point(0.0, 1.5, -4.0),
+        &Tuple::new_point(0.0, 0.0, 0.0),
+        &Tuple::new_vector(0.0, 1.0, 0.0),
+    ));
+
+    let canvas = camera.render(&world);
+    fs::write("./hexagon.ppm", canvas.to_ppm()).unwrap();
+}
@@ -0,0 +1,65 @@
+use raytracer::camera::Camera;
+use raytracer::draw::Color;
+use raytracer::light::PointLight;
+use raytracer::material::Material;
+use raytracer::shape::{Csg, CsgOperation, Cube, Cylinder, Plane, Shape, Sphere};
+use raytracer::transform;
+use raytracer::tuple::Tuple;
+use raytracer::world::World;
+use std::f64::consts::PI;
+use std::fs;
+
+/// A rounded cube with a hole drilled through each axis
+fn drilled_block() -> Box<dyn Shape> {
+    let mut material = Material::new();
+    material.color = Color::new(0.9, 0.3, 0.2);
+    material.specular = 0.4;
+
+    let mut cube = Cube::new();
+    cube.set_material(material.clone());
+    let mut sphere = Sphere::new();
+    sphere.set_transform(transform::scaling(1.35, 1.35, 1.35));
+    sphere.set_material(material.clone());
+    let block = Csg::new(CsgOperation::Intersection, Box::new(cube), Box::new(sphere));
+
+    let mut holes: Box<dyn Shape> = Box::new(block);
+    for rotation in [
+        transform::rotation_x(0.0),
+        transform::rotation_x(PI / 2.0),
+        transform::rotation_z(PI / 2.0),
+    ] {
+        let mut drill = Cylinder::truncated(-2.0, 2.0, true);
+        drill.set_transform(rotation * transform::scaling(0.5, 1.0, 0.5));
+        drill.set_material(material.clone());
+        holes = Box::new(Csg::new(CsgOperation::Difference, holes, Box::new(drill)));
+    }
+
+    holes.set_transform(transform::translation(0.0, 1.0, 0.0) * transform::rotation_y(PI / 6.0));
+    holes
+}
+
+fn main() {
+    let mut floor = Plane::new();
+    let mut material = Material::new();
+    material.color = Color::new(0.8, 0.8, 0.8);
+    material.specular = 0.0;
+    floor.set_material(material);
+
+    let mut world = World::new();
+    world.objects.push(Box::new(floor));
+    world.objects.push(drilled_block());
+    world.lights.push(PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let mut camera = Camera::new(320, 160, PI / 3.0);
+    camera.set_transform(transform::view_transform(
+        &Tuple::new_point(0.0, 3.0, -5.0),
+        &Tuple::new_point(0.0, 1.0, 0.0),
+        &Tuple::new_vector(0.0, 1.0, 0.0),
+    ));
+
+    let canvas = camera.render(&world);
+    fs::write("./csg.ppm", canvas.to_ppm()).unwrap();
+}
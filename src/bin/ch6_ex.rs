@@ -0,0 +1,61 @@
+use raytracer::draw::{Canvas, Color};
+use raytracer::intersection;
+use raytracer::light::{self, PointLight};
+use raytracer::material::Material;
+use raytracer::ray::Ray;
+use raytracer::shape::{Shape, Sphere};
+use raytracer::tuple::Tuple;
+use std::fs;
+
+const CANVAS_PIXELS: usize = 256;
+const WALL_Z: f64 = 10.0;
+const WALL_SIZE: f64 = 7.0;
+
+fn main() {
+    let mut canvas = Canvas::new(CANVAS_PIXELS, CANVAS_PIXELS);
+    let ray_origin = Tuple::new_point(0.0, 0.0, -5.0);
+
+    let pixel_size = WALL_SIZE / CANVAS_PIXELS as f64;
+    let half = WALL_SIZE / 2.0;
+
+    let mut sphere = Sphere::new();
+    let mut material = Material::new();
+    material.color = Color::new(1.0, 0.2, 1.0);
+    sphere.set_material(material);
+
+    let light = PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    );
+
+    for y in 0..CANVAS_PIXELS {
+        // top of the wall is +half, bottom is -half
+        let world_y = half - pixel_size * y as f64;
+
+        for x in 0..CANVAS_PIXELS {
+            let world_x = -half + pixel_size * x as f64;
+            let position = Tuple::new_point(world_x, world_y, WALL_Z);
+
+            let ray = Ray::new(ray_origin.clone(), (position - &ray_origin).normalize());
+            let xs = sphere.intersect(&ray);
+
+            if let Some(hit) = intersection::hit(&xs) {
+                let point = ray.position(hit.t);
+                let normal = hit.object.normal_at(&point);
+                let eye = -ray.direction.clone();
+                let color = light::lighting(
+                    hit.object.material(),
+                    hit.object,
+                    &light,
+                    &point,
+                    &eye,
+                    &normal,
+                    false,
+                );
+                canvas.set_pixel_at(x, y, &color).ok();
+            }
+        }
+    }
+
+    fs::write("./sphere.ppm", canvas.to_ppm()).unwrap();
+}
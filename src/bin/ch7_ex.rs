@@ -0,0 +1,88 @@
+use raytracer::camera::Camera;
+use raytracer::draw::Color;
+use raytracer::light::PointLight;
+use raytracer::material::Material;
+use raytracer::shape::{Shape, Sphere};
+use raytracer::transform;
+use raytracer::tuple::Tuple;
+use raytracer::world::World;
+use std::f64::consts::PI;
+use std::fs;
+
+fn main() {
+    let mut wall_material = Material::new();
+    wall_material.color = Color::new(1.0, 0.9, 0.9);
+    wall_material.specular = 0.0;
+
+    let mut floor = Sphere::new();
+    floor.set_transform(transform::scaling(10.0, 0.01, 10.0));
+    floor.set_material(wall_material.clone());
+
+    let mut left_wall = Sphere::new();
+    left_wall.set_transform(
+        transform::translation(0.0, 0.0, 5.0)
+            * transform::rotation_y(-PI / 4.0)
+            * transform::rotation_x(PI / 2.0)
+            * transform::scaling(10.0, 0.01, 10.0),
+    );
+    left_wall.set_material(wall_material.clone());
+
+    let mut right_wall = Sphere::new();
+    right_wall.set_transform(
+        transform::translation(0.0, 0.0, 5.0)
+            * transform::rotation_y(PI / 4.0)
+            * transform::rotation_x(PI / 2.0)
+            * transform::scaling(10.0, 0.01, 10.0),
+    );
+    right_wall.set_material(wall_material);
+
+    let mut middle = Sphere::new();
+    middle.set_transform(transform::translation(-0.5, 1.0, 0.5));
+    let mut material = Material::new();
+    material.color = Color::new(0.1, 1.0, 0.5);
+    material.diffuse = 0.7;
+    material.specular = 0.3;
+    middle.set_material(material);
+
+    let mut right = Sphere::new();
+    right.set_transform(transform::translation(1.5, 0.5, -0.5) * transform::scaling(0.5, 0.5, 0.5));
+    let mut material = Material::new();
+    material.color = Color::new(0.5, 1.0, 0.1);
+    material.diffuse = 0.7;
+    material.specular = 0.3;
+    right.set_material(material);
+
+    let mut left = Sphere::new();
+    left.set_transform(
+        transform::translation(-1.5, 0.33, -0.75) * transform::scaling(0.33, 0.33, 0.33),
+    );
+    let mut material = Material::new();
+    material.color = Color::new(1.0, 0.8, 0.1);
+    material.diffuse = 0.7;
+    material.specular = 0.3;
+    left.set_material(material);
+
+    let mut world = World::new();
+    world.objects = vec![
+        Box::new(floor),
+        Box::new(left_wall),
+        Box::new(right_wall),
+        Box::new(middle),
+        Box::new(right),
+        Box::new(left),
+    ];
+    world.lights.push(PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let mut camera = Camera::new(320, 160, PI / 3.0);
+    camera.set_transform(transform::view_transform(
+        &Tuple::new_point(0.0, 1.5, -5.0),
+        &Tuple::new_point(0.0, 1.0, 0.0),
+        &Tuple::new_vector(0.0, 1.0, 0.0),
+    ));
+
+    let canvas = camera.render(&world);
+    fs::write("./scene.ppm", canvas.to_ppm()).unwrap();
+}
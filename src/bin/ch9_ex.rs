@@ -0,0 +1,63 @@
+use raytracer::camera::Camera;
+use raytracer::draw::Color;
+use raytracer::light::PointLight;
+use raytracer::material::Material;
+use raytracer::shape::{Plane, Shape, Sphere};
+use raytracer::transform;
+use raytracer::tuple::Tuple;
+use raytracer::world::World;
+use std::f64::consts::PI;
+use std::fs;
+
+fn main() {
+    let mut floor = Plane::new();
+    let mut material = Material::new();
+    material.color = Color::new(1.0, 0.9, 0.9);
+    material.specular = 0.0;
+    floor.set_material(material);
+
+    let mut backdrop = Plane::new();
+    backdrop.set_transform(transform::translation(0.0, 0.0, 5.0) * transform::rotation_x(PI / 2.0));
+    let mut material = Material::new();
+    material.color = Color::new(0.6, 0.7, 1.0);
+    material.specular = 0.0;
+    backdrop.set_material(material);
+
+    let mut middle = Sphere::new();
+    middle.set_transform(transform::translation(-0.5, 1.0, 0.5));
+    let mut material = Material::new();
+    material.color = Color::new(0.1, 1.0, 0.5);
+    material.diffuse = 0.7;
+    material.specular = 0.3;
+    middle.set_material(material);
+
+    let mut right = Sphere::new();
+    right.set_transform(transform::translation(1.5, 0.5, -0.5) * transform::scaling(0.5, 0.5, 0.5));
+    let mut material = Material::new();
+    material.color = Color::new(0.5, 1.0, 0.1);
+    material.diffuse = 0.7;
+    material.specular = 0.3;
+    right.set_material(material);
+
+    let mut world = World::new();
+    world.objects = vec![
+        Box::new(floor),
+        Box::new(backdrop),
+        Box::new(middle),
+        Box::new(right),
+    ];
+    world.lights.push(PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let mut camera = Camera::new(320, 160, PI / 3.0);
+    camera.set_transform(transform::view_transform(
+        &Tuple::new_point(0.0, 1.5, -5.0),
+        &Tuple::new_point(0.0, 1.0, 0.0),
+        &Tuple::new_vector(0.0, 1.0, 0.0),
+    ));
+
+    let canvas = camera.render(&world);
+    fs::write("./planes.ppm", canvas.to_ppm()).unwrap();
+}
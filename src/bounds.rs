@@ -0,0 +1,492 @@
+use crate::{EPSILON, matrix::Matrix4, ray::Ray, tuple::Tuple};
+
+/// An axis-aligned box, given by its minimum and maximum corner points.
+/// Shapes use it to describe the space they occupy, so rays missing the box
+/// can skip testing the shapes inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBox {
+    pub min: Tuple,
+    pub max: Tuple,
+}
+
+impl BoundingBox {
+    /// Creates a box between two corner points
+    pub fn new(min: Tuple, max: Tuple) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates an empty box, which contains nothing and grows to fit
+    /// whatever is added to it
+    pub fn empty() -> Self {
+        Self {
+            min: Tuple::new_point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Tuple::new_point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    /// Creates a box containing all of space
+    pub fn infinite() -> Self {
+        Self {
+            min: Tuple::new_point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: Tuple::new_point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    /// Whether the box contains nothing
+    pub fn is_empty(&self) -> bool {
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        min_x > max_x || min_y > max_y || min_z > max_z
+    }
+
+    /// Grows the box to include a point
+    pub fn add_point(&mut self, point: &Tuple) {
+        let (x, y, z, _) = point.get();
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        self.min = Tuple::new_point(min_x.min(x), min_y.min(y), min_z.min(z));
+        self.max = Tuple::new_point(max_x.max(x), max_y.max(y), max_z.max(z));
+    }
+
+    /// Grows the box to include another box
+    pub fn add_box(&mut self, other: &BoundingBox) {
+        if other.is_empty() {
+            return;
+        }
+        self.add_point(&other.min);
+        self.add_point(&other.max);
+    }
+
+    /// Whether a point lies inside the box (or on its surface)
+    pub fn contains_point(&self, point: &Tuple) -> bool {
+        let (x, y, z, _) = point.get();
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y) && (min_z..=max_z).contains(&z)
+    }
+
+    /// Whether another box lies entirely inside this box
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    /// Returns the eight corner points of the box
+    pub fn corners(&self) -> [Tuple; 8] {
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        [
+            Tuple::new_point(min_x, min_y, min_z),
+            Tuple::new_point(min_x, min_y, max_z),
+            Tuple::new_point(min_x, max_y, min_z),
+            Tuple::new_point(min_x, max_y, max_z),
+            Tuple::new_point(max_x, min_y, min_z),
+            Tuple::new_point(max_x, min_y, max_z),
+            Tuple::new_point(max_x, max_y, min_z),
+            Tuple::new_point(max_x, max_y, max_z),
+        ]
+    }
+
+    /// Transforms the box by transforming all eight of its corners, and
+    /// returning the axis-aligned box containing them
+    pub fn transform(&self, matrix: &Matrix4) -> BoundingBox {
+        let mut result = BoundingBox::empty();
+        if self.is_empty() {
+            return result;
+        }
+        for corner in self.corners() {
+            result.add_point(&transform_point(matrix, &corner));
+        }
+        result
+    }
+
+    /// Returns the center point of the box
+    pub fn center(&self) -> Tuple {
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        Tuple::new_point(
+            (min_x + max_x) / 2.0,
+            (min_y + max_y) / 2.0,
+            (min_z + max_z) / 2.0,
+        )
+    }
+
+    /// Whether a ray passes through the box
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        self.hit_range(ray).is_some()
+    }
+
+    /// Returns the range of t over which a ray is inside the box, if the ray
+    /// passes through it
+    pub fn hit_range(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let (origin_x, origin_y, origin_z, _) = ray.origin.get();
+        let (direction_x, direction_y, direction_z, _) = ray.direction.get();
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+
+        let (xtmin, xtmax) = check_axis(origin_x, direction_x, min_x, max_x);
+        let (ytmin, ytmax) = check_axis(origin_y, direction_y, min_y, max_y);
+        let (ztmin, ztmax) = check_axis(origin_z, direction_z, min_z, max_z);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        (tmin <= tmax).then_some((tmin, tmax))
+    }
+
+    /// Returns the surface area of the box
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let (dx, dy, dz, _) = (&self.max - &self.min).get();
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Whether every coordinate of the box is finite
+    pub fn is_finite(&self) -> bool {
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        [min_x, min_y, min_z, max_x, max_y, max_z]
+            .iter()
+            .all(|c| c.is_finite())
+    }
+
+    /// Splits the box in two halves, across its largest dimension
+    pub fn split(&self) -> (BoundingBox, BoundingBox) {
+        let (min_x, min_y, min_z, _) = self.min.get();
+        let (max_x, max_y, max_z, _) = self.max.get();
+        let (dx, dy, dz) = (max_x - min_x, max_y - min_y, max_z - min_z);
+        let greatest = dx.max(dy).max(dz);
+
+        // the corners of the splitting plane, closest to min and to max
+        let (mid_min, mid_max) = if greatest == dx {
+            let x = min_x + dx / 2.0;
+            ((x, min_y, min_z), (x, max_y, max_z))
+        } else if greatest == dy {
+            let y = min_y + dy / 2.0;
+            ((min_x, y, min_z), (max_x, y, max_z))
+        } else {
+            let z = min_z + dz / 2.0;
+            ((min_x, min_y, z), (max_x, max_y, z))
+        };
+
+        let mid_min = Tuple::new_point(mid_min.0, mid_min.1, mid_min.2);
+        let mid_max = Tuple::new_point(mid_max.0, mid_max.1, mid_max.2);
+        (
+            BoundingBox::new(self.min.clone(), mid_max),
+            BoundingBox::new(mid_min, self.max.clone()),
+        )
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// Transforms a point, skipping the matrix entries that are zero. Corners
+/// of infinite boxes (such as a plane's) would otherwise produce NaN from
+/// `0 * inf`.
+fn transform_point(matrix: &Matrix4, point: &Tuple) -> Tuple {
+    let (x, y, z, w) = point.get();
+    let coords = [x, y, z, w];
+    let mut result = [0.0; 3];
+
+    for (row, value) in result.iter_mut().enumerate() {
+        for (col, coord) in coords.iter().enumerate() {
+            let entry = matrix.get(row, col).unwrap_or(0.0);
+            if entry != 0.0 {
+                *value += entry * coord;
+            }
+        }
+    }
+    Tuple::new_point(result[0], result[1], result[2])
+}
+
+/// Intersects a ray, reduced to a single axis, with the slab between
+/// `min` and `max` on that axis. Returns the t at which the ray enters and
+/// exits the slab. A ray parallel to the slab is either always inside it,
+/// giving (-inf, inf), or never, giving (inf, -inf).
+pub(crate) fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    if direction.abs() < EPSILON {
+        return if origin >= min && origin <= max {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        } else {
+            (f64::INFINITY, f64::NEG_INFINITY)
+        };
+    }
+
+    let tmin = (min - origin) / direction;
+    let tmax = (max - origin) / direction;
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform;
+    use std::f64::consts::{PI, SQRT_2};
+
+    #[test]
+    fn creating_an_empty_bounding_box() {
+        let bounds = BoundingBox::empty();
+
+        assert!(bounds.is_empty());
+        assert_eq!(bounds.min.get().0, f64::INFINITY);
+        assert_eq!(bounds.max.get().0, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn adding_points_to_an_empty_bounding_box() {
+        let mut bounds = BoundingBox::empty();
+        bounds.add_point(&Tuple::new_point(-5.0, 2.0, 0.0));
+        bounds.add_point(&Tuple::new_point(7.0, 0.0, -3.0));
+
+        assert_eq!(bounds.min, Tuple::new_point(-5.0, 0.0, -3.0));
+        assert_eq!(bounds.max, Tuple::new_point(7.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn adding_one_bounding_box_to_another() {
+        let mut bounds = BoundingBox::new(
+            Tuple::new_point(-5.0, -2.0, 0.0),
+            Tuple::new_point(7.0, 4.0, 4.0),
+        );
+        bounds.add_box(&BoundingBox::new(
+            Tuple::new_point(8.0, -7.0, -2.0),
+            Tuple::new_point(14.0, 2.0, 8.0),
+        ));
+        bounds.add_box(&BoundingBox::empty());
+
+        assert_eq!(bounds.min, Tuple::new_point(-5.0, -7.0, -2.0));
+        assert_eq!(bounds.max, Tuple::new_point(14.0, 4.0, 8.0));
+    }
+
+    #[test]
+    fn checking_box_contains_point() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(5.0, -2.0, 0.0),
+            Tuple::new_point(11.0, 4.0, 7.0),
+        );
+        let cases = [
+            (Tuple::new_point(5.0, -2.0, 0.0), true),
+            (Tuple::new_point(11.0, 4.0, 7.0), true),
+            (Tuple::new_point(8.0, 1.0, 3.0), true),
+            (Tuple::new_point(3.0, 0.0, 3.0), false),
+            (Tuple::new_point(8.0, -4.0, 3.0), false),
+            (Tuple::new_point(8.0, 1.0, -1.0), false),
+            (Tuple::new_point(13.0, 1.0, 3.0), false),
+            (Tuple::new_point(8.0, 5.0, 3.0), false),
+            (Tuple::new_point(8.0, 1.0, 8.0), false),
+        ];
+
+        for (point, expected) in cases {
+            assert_eq!(bounds.contains_point(&point), expected, "{point}");
+        }
+    }
+
+    #[test]
+    fn checking_box_contains_box() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(5.0, -2.0, 0.0),
+            Tuple::new_point(11.0, 4.0, 7.0),
+        );
+        let cases = [
+            ((5.0, -2.0, 0.0), (11.0, 4.0, 7.0), true),
+            ((6.0, -1.0, 1.0), (10.0, 3.0, 6.0), true),
+            ((4.0, -3.0, -1.0), (10.0, 3.0, 6.0), false),
+            ((6.0, -1.0, 1.0), (12.0, 5.0, 8.0), false),
+        ];
+
+        for ((x0, y0, z0), (x1, y1, z1), expected) in cases {
+            let other =
+                BoundingBox::new(Tuple::new_point(x0, y0, z0), Tuple::new_point(x1, y1, z1));
+            assert_eq!(bounds.contains_box(&other), expected);
+        }
+    }
+
+    #[test]
+    fn transforming_a_bounding_box() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(-1.0, -1.0, -1.0),
+            Tuple::new_point(1.0, 1.0, 1.0),
+        );
+        let matrix = transform::rotation_x(PI / 4.0) * transform::rotation_y(PI / 4.0);
+        let transformed = bounds.transform(&matrix);
+
+        assert_eq!(
+            transformed.min,
+            Tuple::new_point(-SQRT_2, -1.70711, -1.70711)
+        );
+        assert_eq!(transformed.max, Tuple::new_point(SQRT_2, 1.70711, 1.70711));
+    }
+
+    #[test]
+    // Translating an infinite box keeps it infinite, instead of producing
+    // NaN from 0 * inf
+    fn transforming_an_infinite_bounding_box() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Tuple::new_point(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+        let transformed = bounds.transform(&transform::translation(1.0, 2.0, 3.0));
+
+        assert_eq!(
+            transformed.min.get(),
+            (f64::NEG_INFINITY, 2.0, f64::NEG_INFINITY, 1.0)
+        );
+        assert_eq!(
+            transformed.max.get(),
+            (f64::INFINITY, 2.0, f64::INFINITY, 1.0)
+        );
+    }
+
+    #[test]
+    // Intersecting a ray with a bounding box at the origin
+    fn intersecting_ray_with_bounding_box_at_origin() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(-1.0, -1.0, -1.0),
+            Tuple::new_point(1.0, 1.0, 1.0),
+        );
+        let cases = [
+            ((5.0, 0.5, 0.0), (-1.0, 0.0, 0.0), true),
+            ((-5.0, 0.5, 0.0), (1.0, 0.0, 0.0), true),
+            ((0.5, 5.0, 0.0), (0.0, -1.0, 0.0), true),
+            ((0.5, -5.0, 0.0), (0.0, 1.0, 0.0), true),
+            ((0.5, 0.0, 5.0), (0.0, 0.0, -1.0), true),
+            ((0.5, 0.0, -5.0), (0.0, 0.0, 1.0), true),
+            ((0.0, 0.5, 0.0), (0.0, 0.0, 1.0), true),
+            ((-2.0, 0.0, 0.0), (2.0, 4.0, 6.0), false),
+            ((0.0, -2.0, 0.0), (6.0, 2.0, 4.0), false),
+            ((0.0, 0.0, -2.0), (4.0, 6.0, 2.0), false),
+            ((2.0, 0.0, 2.0), (0.0, 0.0, -1.0), false),
+            ((0.0, 2.0, 2.0), (0.0, -1.0, 0.0), false),
+            ((2.0, 2.0, 0.0), (-1.0, 0.0, 0.0), false),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), expected) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            assert_eq!(bounds.intersects(&ray), expected);
+        }
+    }
+
+    #[test]
+    // Intersecting a ray with a non-cubic bounding box
+    fn intersecting_ray_with_non_cubic_bounding_box() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(5.0, -2.0, 0.0),
+            Tuple::new_point(11.0, 4.0, 7.0),
+        );
+        let cases = [
+            ((15.0, 1.0, 2.0), (-1.0, 0.0, 0.0), true),
+            ((-5.0, -1.0, 4.0), (1.0, 0.0, 0.0), true),
+            ((7.0, 6.0, 5.0), (0.0, -1.0, 0.0), true),
+            ((9.0, -5.0, 6.0), (0.0, 1.0, 0.0), true),
+            ((8.0, 2.0, 12.0), (0.0, 0.0, -1.0), true),
+            ((6.0, 0.0, -5.0), (0.0, 0.0, 1.0), true),
+            ((8.0, 1.0, 3.5), (0.0, 0.0, 1.0), true),
+            ((9.0, -1.0, -8.0), (2.0, 4.0, 6.0), false),
+            ((8.0, 3.0, -4.0), (6.0, 2.0, 4.0), false),
+            ((9.0, -1.0, -2.0), (4.0, 6.0, 2.0), false),
+            ((4.0, 0.0, 9.0), (0.0, 0.0, -1.0), false),
+            ((8.0, 6.0, -1.0), (0.0, -1.0, 0.0), false),
+            ((12.0, 5.0, 4.0), (-1.0, 0.0, 0.0), false),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), expected) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            assert_eq!(bounds.intersects(&ray), expected);
+        }
+    }
+
+    #[test]
+    fn range_of_ray_inside_bounding_box() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(-1.0, -1.0, -1.0),
+            Tuple::new_point(1.0, 1.0, 1.0),
+        );
+        let hit = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let miss = Ray::new(
+            Tuple::new_point(0.0, 2.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert_eq!(bounds.hit_range(&hit), Some((4.0, 6.0)));
+        assert_eq!(bounds.hit_range(&miss), None);
+    }
+
+    #[test]
+    fn surface_area_of_bounding_box() {
+        let bounds = BoundingBox::new(
+            Tuple::new_point(-1.0, 0.0, 2.0),
+            Tuple::new_point(1.0, 3.0, 6.0),
+        );
+
+        assert_eq!(
+            bounds.surface_area(),
+            2.0 * (2.0 * 3.0 + 3.0 * 4.0 + 4.0 * 2.0)
+        );
+        assert_eq!(BoundingBox::empty().surface_area(), 0.0);
+        assert!(bounds.is_finite());
+        assert!(!BoundingBox::infinite().is_finite());
+    }
+
+    #[test]
+    fn splitting_bounding_boxes() {
+        // (min, max) of the box, then the expected (left max, right min)
+        let cases = [
+            // a perfect cube splits on x
+            (
+                (-1.0, -4.0, -5.0),
+                (9.0, 6.0, 5.0),
+                (4.0, 6.0, 5.0),
+                (4.0, -4.0, -5.0),
+            ),
+            // x-wide
+            (
+                (-1.0, -2.0, -3.0),
+                (9.0, 5.5, 3.0),
+                (4.0, 5.5, 3.0),
+                (4.0, -2.0, -3.0),
+            ),
+            // y-wide
+            (
+                (-1.0, -2.0, -3.0),
+                (5.0, 8.0, 3.0),
+                (5.0, 3.0, 3.0),
+                (-1.0, 3.0, -3.0),
+            ),
+            // z-wide
+            (
+                (-1.0, -2.0, -3.0),
+                (5.0, 3.0, 7.0),
+                (5.0, 3.0, 2.0),
+                (-1.0, -2.0, 2.0),
+            ),
+        ];
+
+        for ((x0, y0, z0), (x1, y1, z1), (lx, ly, lz), (rx, ry, rz)) in cases {
+            let bounds =
+                BoundingBox::new(Tuple::new_point(x0, y0, z0), Tuple::new_point(x1, y1, z1));
+            let (left, right) = bounds.split();
+
+            assert_eq!(left.min, bounds.min);
+            assert_eq!(left.max, Tuple::new_point(lx, ly, lz));
+            assert_eq!(right.min, Tuple::new_point(rx, ry, rz));
+            assert_eq!(right.max, bounds.max);
+        }
+    }
+}
@@ -0,0 +1,759 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{self, Intersection},
+    matrix::Matrix4,
+    ray::Ray,
+    shape::{Group, ParentTransform, Shape, ShapeData},
+    tuple::Tuple,
+};
+use std::{
+    any::Any,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Largest number of primitives in a leaf, unless they can't be told apart
+pub const DEFAULT_MAX_LEAF_SIZE: usize = 4;
+
+/// Number of buckets the primitives are binned into when looking for the
+/// cheapest split
+const SAH_BUCKETS: usize = 12;
+
+/// Cost of visiting a node, relative to the cost of intersecting a
+/// primitive
+const TRAVERSAL_COST: f64 = 0.125;
+
+/// A node of the flattened tree
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: BoundingBox,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NodeKind {
+    /// Holds the primitives `first..first + count`
+    Leaf { first: usize, count: usize },
+    /// The first child directly follows the node in the array, the second
+    /// one is at `second`. The primitives were split along `axis` (0 for x,
+    /// 1 for y, 2 for z), which orders the children front to back.
+    Interior { second: usize, axis: usize },
+}
+
+/// Figures about a built BVH, to help tune it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BvhStats {
+    /// Primitives stored in the tree
+    pub primitives: usize,
+    /// Primitives with infinite bounds (such as planes), kept out of the
+    /// tree and tested against every ray
+    pub unbounded: usize,
+    pub nodes: usize,
+    pub leaves: usize,
+    /// Number of nodes on the longest path from the root to a leaf
+    pub depth: usize,
+    pub min_leaf_size: usize,
+    pub max_leaf_size: usize,
+    pub mean_leaf_size: f64,
+    pub build_time: Duration,
+}
+
+impl fmt::Display for BvhStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} primitives ({} unbounded), {} nodes, {} leaves, depth {}, \
+             leaf size {}..={} (mean {:.2}), built in {:?}",
+            self.primitives,
+            self.unbounded,
+            self.nodes,
+            self.leaves,
+            self.depth,
+            self.min_leaf_size,
+            self.max_leaf_size,
+            self.mean_leaf_size,
+            self.build_time
+        )
+    }
+}
+
+/// A primitive, as seen while building the tree
+struct BuildItem {
+    /// Position of the primitive in the list given to the builder
+    index: usize,
+    bounds: BoundingBox,
+    centroid: Tuple,
+}
+
+/// A bounding volume hierarchy: a container of shapes, like a group, that
+/// organizes them in a tree of bounding boxes so rays only test the shapes
+/// whose boxes they pass through. The tree is built using the surface area
+/// heuristic, and stored as a flat array of nodes.
+#[derive(Debug)]
+pub struct Bvh {
+    data: ShapeData,
+    nodes: Vec<BvhNode>,
+    /// Primitives, in the order the leaves refer to them
+    primitives: Vec<Box<dyn Shape>>,
+    unbounded: Vec<Box<dyn Shape>>,
+    bounds: BoundingBox,
+    stats: BvhStats,
+}
+
+impl Bvh {
+    /// Builds a BVH over the given shapes, with leaves of at most
+    /// `DEFAULT_MAX_LEAF_SIZE` shapes
+    pub fn new(shapes: Vec<Box<dyn Shape>>) -> Self {
+        Self::with_max_leaf_size(shapes, DEFAULT_MAX_LEAF_SIZE)
+    }
+
+    /// Builds a BVH over the given shapes, with leaves of at most
+    /// `max_leaf_size` shapes. Leaves may be larger when their shapes have
+    /// the same center, as there is no way to split them.
+    pub fn with_max_leaf_size(shapes: Vec<Box<dyn Shape>>, max_leaf_size: usize) -> Self {
+        let start = Instant::now();
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = shapes
+            .into_iter()
+            .partition(|shape| shape.parent_space_bounds().is_finite());
+
+        let mut items: Vec<BuildItem> = bounded
+            .iter()
+            .enumerate()
+            .map(|(index, shape)| {
+                let bounds = shape.parent_space_bounds();
+                BuildItem {
+                    index,
+                    centroid: bounds.center(),
+                    bounds,
+                }
+            })
+            .collect();
+
+        let mut builder = Builder {
+            nodes: vec![],
+            order: vec![],
+            max_leaf_size: max_leaf_size.max(1),
+            stats: BvhStats {
+                primitives: bounded.len(),
+                unbounded: unbounded.len(),
+                ..Default::default()
+            },
+        };
+        if !items.is_empty() {
+            builder.build(&mut items, 1);
+        }
+
+        // lay the primitives out in the order the leaves refer to them
+        let mut slots: Vec<Option<Box<dyn Shape>>> = bounded.into_iter().map(Some).collect();
+        let primitives = builder
+            .order
+            .iter()
+            .filter_map(|&index| slots[index].take())
+            .collect();
+
+        let mut bounds = builder
+            .nodes
+            .first()
+            .map_or_else(BoundingBox::empty, |root| root.bounds.clone());
+        for shape in &unbounded {
+            bounds.add_box(&shape.parent_space_bounds());
+        }
+
+        let mut stats = builder.stats;
+        stats.nodes = builder.nodes.len();
+        if stats.leaves > 0 {
+            stats.mean_leaf_size = stats.primitives as f64 / stats.leaves as f64;
+        }
+        stats.build_time = start.elapsed();
+
+        let mut bvh = Self {
+            data: ShapeData::new(),
+            nodes: builder.nodes,
+            primitives,
+            unbounded,
+            bounds,
+            stats,
+        };
+        bvh.link_children();
+        bvh
+    }
+
+    /// Builds a BVH over the shapes of a group, taking over its transform.
+    /// Nested groups without a transform of their own are flattened, so the
+    /// tree is built over the shapes they contain.
+    pub fn from_group(group: Group) -> Self {
+        let transform = group.transform().clone();
+        let parent = group.parent().cloned();
+        let mut shapes = vec![];
+        flatten_into(group.into_children(), &mut shapes);
+
+        let mut bvh = Self::new(shapes);
+        bvh.data.set_transform(transform);
+        bvh.set_parent(parent);
+        bvh
+    }
+
+    /// Returns figures about the tree built
+    pub fn stats(&self) -> &BvhStats {
+        &self.stats
+    }
+
+    /// Visits the primitives in the leaves whose boxes the ray passes
+    /// through between `t_min` and `t_max`, nearest child first, until
+    /// `visit` returns true
+    fn traverse<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        mut visit: impl FnMut(&'a dyn Shape) -> bool,
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let (dx, dy, dz, _) = ray.direction.get();
+        let dir_is_neg = [dx < 0.0, dy < 0.0, dz < 0.0];
+        let mut stack = Vec::with_capacity(64);
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+            let hit = node
+                .bounds
+                .hit_range(ray)
+                .is_some_and(|(near, far)| near <= t_max && far >= t_min);
+
+            if hit {
+                match node.kind {
+                    NodeKind::Leaf { first, count } => {
+                        for primitive in &self.primitives[first..first + count] {
+                            if visit(primitive.as_ref()) {
+                                return;
+                            }
+                        }
+                    }
+                    NodeKind::Interior { second, axis } => {
+                        // visit the child on the ray's side first
+                        if dir_is_neg[axis] {
+                            stack.push(current + 1);
+                            current = second;
+                        } else {
+                            stack.push(second);
+                            current += 1;
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            match stack.pop() {
+                Some(next) => current = next,
+                None => return,
+            }
+        }
+    }
+
+    /// Hands an up-to-date parent link to every primitive
+    fn link_children(&mut self) {
+        let link = self.data.link();
+        for shape in self.primitives.iter_mut().chain(self.unbounded.iter_mut()) {
+            shape.set_parent(Some(link.clone()));
+        }
+    }
+}
+
+impl Shape for Bvh {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.data.set_transform(transform);
+        self.link_children();
+    }
+
+    fn set_parent(&mut self, parent: Option<Arc<ParentTransform>>) {
+        self.data.set_parent(parent);
+        self.link_children();
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.primitives
+            .iter()
+            .chain(&self.unbounded)
+            .any(|shape| shape.includes(other))
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    /// The tree is already organized, so dividing has no effect
+    fn divide(&mut self, _threshold: usize) {}
+
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs = vec![];
+        self.traverse(local_ray, f64::NEG_INFINITY, f64::INFINITY, |shape| {
+            xs.extend(shape.intersect(local_ray));
+            false
+        });
+        for shape in &self.unbounded {
+            xs.extend(shape.intersect(local_ray));
+        }
+        intersection::intersections(xs)
+    }
+
+    /// Stops at the first shadow casting shape hit, rather than collecting
+    /// every intersection
+    fn occludes(&self, ray: &Ray, distance: f64) -> bool {
+        // transforming the ray keeps t unchanged, as the direction isn't
+        // normalized
+        let local_ray = ray.transform(self.inverse());
+        let mut occluded = self
+            .unbounded
+            .iter()
+            .any(|shape| shape.occludes(&local_ray, distance));
+
+        if !occluded {
+            self.traverse(&local_ray, 0.0, distance, |shape| {
+                occluded = shape.occludes(&local_ray, distance);
+                occluded
+            });
+        }
+        occluded
+    }
+
+    /// ## Panics
+    /// Always panics: normals are computed on the primitives that were hit.
+    fn local_normal_at(&self, _local_point: &Tuple) -> Tuple {
+        unreachable!("BVHs have no surface; normals are computed on their primitives")
+    }
+}
+
+/// Moves shapes into `shapes`, replacing groups without a transform by
+/// their own children
+fn flatten_into(children: Vec<Box<dyn Shape>>, shapes: &mut Vec<Box<dyn Shape>>) {
+    for child in children {
+        let is_plain_group = (child.as_ref() as &dyn Any)
+            .downcast_ref::<Group>()
+            .is_some_and(|group| *group.transform() == Matrix4::identity());
+
+        if is_plain_group {
+            let group = (child as Box<dyn Any>)
+                .downcast::<Group>()
+                .expect("Child should be a group");
+            flatten_into(group.into_children(), shapes);
+        } else {
+            shapes.push(child);
+        }
+    }
+}
+
+/// Returns the x, y or z coordinate of a tuple
+fn component(tuple: &Tuple, axis: usize) -> f64 {
+    let (x, y, z, _) = tuple.get();
+    [x, y, z][axis]
+}
+
+/// Builds the flattened tree, depth first
+struct Builder {
+    nodes: Vec<BvhNode>,
+    /// Indices of the primitives, in the order leaves refer to them
+    order: Vec<usize>,
+    max_leaf_size: usize,
+    stats: BvhStats,
+}
+
+impl Builder {
+    /// Builds the subtree holding the given primitives, returning the index
+    /// of its root node
+    fn build(&mut self, items: &mut [BuildItem], depth: usize) -> usize {
+        let mut bounds = BoundingBox::empty();
+        for item in items.iter() {
+            bounds.add_box(&item.bounds);
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounds: bounds.clone(),
+            kind: NodeKind::Leaf { first: 0, count: 0 },
+        });
+
+        match self.find_split(items, &bounds) {
+            Some((axis, mid)) => {
+                let (left, right) = items.split_at_mut(mid);
+                self.build(left, depth + 1);
+                let second = self.build(right, depth + 1);
+                self.nodes[index].kind = NodeKind::Interior { second, axis };
+            }
+            None => {
+                let first = self.order.len();
+                self.order.extend(items.iter().map(|item| item.index));
+                self.nodes[index].kind = NodeKind::Leaf {
+                    first,
+                    count: items.len(),
+                };
+                self.record_leaf(items.len(), depth);
+            }
+        }
+        index
+    }
+
+    /// Finds the cheapest way of splitting the primitives in two, according
+    /// to the surface area heuristic, and partitions them accordingly.
+    /// Returns the axis of the split and the number of primitives on the
+    /// first side, or None when a leaf is cheaper (or the only option).
+    fn find_split(&self, items: &mut [BuildItem], bounds: &BoundingBox) -> Option<(usize, usize)> {
+        let count = items.len();
+        if count <= 1 {
+            return None;
+        }
+
+        // split along the axis the centers are most spread out on
+        let mut centroid_bounds = BoundingBox::empty();
+        for item in items.iter() {
+            centroid_bounds.add_point(&item.centroid);
+        }
+        let extent = &centroid_bounds.max - &centroid_bounds.min;
+        let axis = (0..3)
+            .max_by(|&a, &b| component(&extent, a).total_cmp(&component(&extent, b)))
+            .unwrap_or(0);
+        let min = component(&centroid_bounds.min, axis);
+        let width = component(&extent, axis);
+        // primitives sharing the same center can't be told apart
+        if width <= 0.0 {
+            return None;
+        }
+
+        let bucket_of = |item: &BuildItem| {
+            let offset = (component(&item.centroid, axis) - min) / width;
+            ((offset * SAH_BUCKETS as f64) as usize).min(SAH_BUCKETS - 1)
+        };
+
+        let mut counts = [0usize; SAH_BUCKETS];
+        let mut bucket_bounds: Vec<BoundingBox> = vec![BoundingBox::empty(); SAH_BUCKETS];
+        for item in items.iter() {
+            let bucket = bucket_of(item);
+            counts[bucket] += 1;
+            bucket_bounds[bucket].add_box(&item.bounds);
+        }
+
+        // cost of splitting after each bucket: the cost of visiting a node,
+        // plus the cost of intersecting each side weighted by the
+        // probability of a ray hitting it
+        let area = bounds.surface_area().max(f64::MIN_POSITIVE);
+        let (best_bucket, best_cost) = (0..SAH_BUCKETS - 1)
+            .map(|split| {
+                let side = |range: std::ops::Range<usize>| {
+                    let mut side_bounds = BoundingBox::empty();
+                    let mut side_count = 0;
+                    for bucket in range {
+                        side_bounds.add_box(&bucket_bounds[bucket]);
+                        side_count += counts[bucket];
+                    }
+                    side_count as f64 * side_bounds.surface_area()
+                };
+                let cost =
+                    TRAVERSAL_COST + (side(0..split + 1) + side(split + 1..SAH_BUCKETS)) / area;
+                (split, cost)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, f64::INFINITY));
+
+        let leaf_cost = count as f64;
+        if count <= self.max_leaf_size && leaf_cost <= best_cost {
+            return None;
+        }
+
+        let mut mid = partition(items, |item| bucket_of(item) <= best_bucket);
+        if mid == 0 || mid == count {
+            // every primitive fell on one side: split them by count instead
+            mid = count / 2;
+            items.select_nth_unstable_by(mid, |a, b| {
+                component(&a.centroid, axis).total_cmp(&component(&b.centroid, axis))
+            });
+        }
+        Some((axis, mid))
+    }
+
+    fn record_leaf(&mut self, size: usize, depth: usize) {
+        let stats = &mut self.stats;
+        stats.min_leaf_size = if stats.leaves == 0 {
+            size
+        } else {
+            stats.min_leaf_size.min(size)
+        };
+        stats.max_leaf_size = stats.max_leaf_size.max(size);
+        stats.depth = stats.depth.max(depth);
+        stats.leaves += 1;
+    }
+}
+
+/// Moves the items matching the predicate to the front of the slice,
+/// returning how many there are
+fn partition<T>(items: &mut [T], predicate: impl Fn(&T) -> bool) -> usize {
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if predicate(&items[i]) {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shape::{Plane, Sphere},
+        transform,
+    };
+
+    /// Small spheres on a n x n x n grid, 3 units apart
+    fn sphere_grid(n: usize) -> Vec<Box<dyn Shape>> {
+        let mut shapes: Vec<Box<dyn Shape>> = vec![];
+        for x in 0..n {
+            for y in 0..n {
+                for z in 0..n {
+                    let mut sphere = Sphere::new();
+                    sphere.set_transform(
+                        transform::translation(x as f64 * 3.0, y as f64 * 3.0, z as f64 * 3.0)
+                            * transform::scaling(0.5, 0.5, 0.5),
+                    );
+                    shapes.push(Box::new(sphere));
+                }
+            }
+        }
+        shapes
+    }
+
+    /// Spheres on the x axis, 3 units apart
+    fn sphere_row(n: usize) -> Vec<Box<dyn Shape>> {
+        (0..n)
+            .map(|x| {
+                let mut sphere = Sphere::new();
+                sphere.set_transform(transform::translation(x as f64 * 3.0, 0.0, 0.0));
+                Box::new(sphere) as Box<dyn Shape>
+            })
+            .collect()
+    }
+
+    fn rays() -> Vec<Ray> {
+        vec![
+            Ray::new(
+                Tuple::new_point(-5.0, 0.0, 0.0),
+                Tuple::new_vector(1.0, 0.0, 0.0),
+            ),
+            Ray::new(
+                Tuple::new_point(3.0, 3.0, -5.0),
+                Tuple::new_vector(0.0, 0.0, 1.0),
+            ),
+            Ray::new(
+                Tuple::new_point(-5.0, -5.0, -5.0),
+                Tuple::new_vector(1.0, 1.0, 1.0).normalize(),
+            ),
+            Ray::new(
+                Tuple::new_point(20.0, 4.5, 6.0),
+                Tuple::new_vector(-1.0, -0.1, 0.05).normalize(),
+            ),
+            Ray::new(
+                Tuple::new_point(1.5, 1.5, 1.5),
+                Tuple::new_vector(0.0, 1.0, 0.0),
+            ),
+        ]
+    }
+
+    #[test]
+    fn building_empty_bvh() {
+        let bvh = Bvh::new(vec![]);
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert_eq!(bvh.stats().nodes, 0);
+        assert!(bvh.bounds().is_empty());
+        assert!(bvh.intersect(&ray).is_empty());
+        assert!(!bvh.occludes(&ray, f64::INFINITY));
+    }
+
+    #[test]
+    // A BVH finds the same intersections as a group of the same shapes
+    fn intersections_match_group() {
+        let mut group = Group::new();
+        for shape in sphere_grid(4) {
+            group.add_child(shape);
+        }
+        let bvh = Bvh::new(sphere_grid(4));
+
+        for ray in rays() {
+            let expected: Vec<f64> = group.intersect(&ray).iter().map(|i| i.t).collect();
+            let actual: Vec<f64> = bvh.intersect(&ray).iter().map(|i| i.t).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn build_statistics() {
+        let bvh = Bvh::with_max_leaf_size(sphere_grid(4), 2);
+        let stats = bvh.stats();
+
+        assert_eq!(stats.primitives, 64);
+        assert_eq!(stats.unbounded, 0);
+        // every interior node has exactly two children
+        assert_eq!(stats.nodes, 2 * stats.leaves - 1);
+        assert!(stats.min_leaf_size >= 1);
+        assert!(stats.max_leaf_size <= 2);
+        assert_eq!(stats.mean_leaf_size, 64.0 / stats.leaves as f64);
+        assert!(stats.depth >= 6);
+        assert!(stats.to_string().starts_with("64 primitives (0 unbounded)"));
+    }
+
+    #[test]
+    // Leaves refer to disjoint ranges covering every primitive
+    fn leaves_cover_every_primitive() {
+        let bvh = Bvh::new(sphere_grid(3));
+        let mut covered = vec![false; bvh.primitives.len()];
+
+        for node in &bvh.nodes {
+            if let NodeKind::Leaf { first, count } = node.kind {
+                for slot in &mut covered[first..first + count] {
+                    assert!(!*slot);
+                    *slot = true;
+                }
+            }
+        }
+        assert!(covered.iter().all(|&slot| slot));
+    }
+
+    #[test]
+    // Children are visited front to back along the ray
+    fn traversal_visits_nearest_child_first() {
+        let bvh = Bvh::with_max_leaf_size(sphere_row(8), 1);
+        let first_visited = |ray: &Ray| {
+            let mut first = None;
+            bvh.traverse(ray, f64::NEG_INFINITY, f64::INFINITY, |shape| {
+                first = Some(shape.transform().clone());
+                true
+            });
+            first.unwrap()
+        };
+
+        let forward = Ray::new(
+            Tuple::new_point(-10.0, 0.0, 0.0),
+            Tuple::new_vector(1.0, 0.0, 0.0),
+        );
+        let backward = Ray::new(
+            Tuple::new_point(40.0, 0.0, 0.0),
+            Tuple::new_vector(-1.0, 0.0, 0.0),
+        );
+
+        assert_eq!(
+            first_visited(&forward),
+            transform::translation(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            first_visited(&backward),
+            transform::translation(21.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    // Shadow rays stop at the first shadow casting shape in range
+    fn occluding_shadow_rays() {
+        let mut shapes = sphere_row(4);
+        shapes[1].set_casts_shadows(false);
+        let bvh = Bvh::new(shapes);
+        let ray = Ray::new(
+            Tuple::new_point(-5.0, 0.0, 0.0),
+            Tuple::new_vector(1.0, 0.0, 0.0),
+        );
+
+        // the first sphere spans t = 4..6
+        assert!(!bvh.occludes(&ray, 3.5));
+        assert!(bvh.occludes(&ray, 4.5));
+
+        // starting past the first sphere, the second one doesn't cast
+        // shadows
+        let ray = Ray::new(
+            Tuple::new_point(1.5, 0.0, 0.0),
+            Tuple::new_vector(1.0, 0.0, 0.0),
+        );
+        assert!(!bvh.occludes(&ray, 3.0));
+        assert!(bvh.occludes(&ray, 4.0));
+    }
+
+    #[test]
+    // Shapes with infinite bounds are kept out of the tree, but still hit
+    fn unbounded_shapes() {
+        let mut shapes = sphere_row(2);
+        let mut floor = Plane::new();
+        floor.set_transform(transform::translation(0.0, -1.0, 0.0));
+        shapes.push(Box::new(floor));
+        let bvh = Bvh::new(shapes);
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 5.0, 0.0),
+            Tuple::new_vector(0.0, -1.0, 0.0),
+        );
+        let xs = bvh.intersect(&ray);
+
+        assert_eq!(bvh.stats().primitives, 2);
+        assert_eq!(bvh.stats().unbounded, 1);
+        assert_eq!(xs.iter().map(|i| i.t).collect::<Vec<_>>(), [4.0, 6.0, 6.0]);
+        assert!(bvh.occludes(&ray, 10.0));
+    }
+
+    #[test]
+    fn transformed_bvh() {
+        let mut bvh = Bvh::new(sphere_row(3));
+        bvh.set_transform(transform::translation(0.0, 10.0, 0.0));
+        let ray = Ray::new(
+            Tuple::new_point(3.0, 10.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = bvh.intersect(&ray);
+        let normal = xs[0].object.normal_at(&ray.position(xs[0].t));
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(normal, Tuple::new_vector(0.0, 0.0, -1.0));
+        assert!(bvh.occludes(&ray, 5.0));
+    }
+
+    #[test]
+    // Building from a group keeps its transform, and flattens nested groups
+    // unless they are transformed themselves
+    fn building_from_group() {
+        let mut plain = Group::new();
+        for shape in sphere_row(3) {
+            plain.add_child(shape);
+        }
+        let mut transformed = Group::new();
+        transformed.set_transform(transform::translation(0.0, 3.0, 0.0));
+        for shape in sphere_row(2) {
+            transformed.add_child(shape);
+        }
+        let mut group = Group::new();
+        group.set_transform(transform::translation(0.0, 0.0, 10.0));
+        group.add_child(Box::new(plain));
+        group.add_child(Box::new(transformed));
+        group.add_child(Box::new(Sphere::new()));
+
+        let bvh = Bvh::from_group(group);
+        let ray = Ray::new(
+            Tuple::new_point(3.0, 3.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert_eq!(bvh.stats().primitives, 5);
+        assert_eq!(*bvh.transform(), transform::translation(0.0, 0.0, 10.0));
+        assert_eq!(bvh.intersect(&ray)[0].t, 9.0);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{
+    draw::Canvas,
+    matrix::Matrix4,
+    ray::Ray,
+    render::{self, Progress, RenderOptions, RenderOutput},
+    tuple::Tuple,
+    world::World,
+};
+use std::sync::atomic::AtomicBool;
+
+/// A camera mapping a 3D scene onto a 2D canvas. The canvas is always one
+/// unit in front of the camera, and the camera transform orients the world
+/// relative to it (see `transform::view_transform`).
+#[derive(Debug, Clone)]
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: f64,
+    transform: Matrix4,
+    inverse: Matrix4,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+}
+
+impl Camera {
+    /// Creates a camera rendering a `hsize` x `vsize` pixels image, with
+    /// field_of_view being the horizontal (or vertical, for portrait
+    /// images) angle in radian that the camera can see
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+
+        Self {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            half_width,
+            half_height,
+            pixel_size: (half_width * 2.0) / hsize as f64,
+        }
+    }
+
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    /// Returns the size of a single pixel on the canvas, in world units
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    /// Returns the view transformation matrix of the camera
+    pub fn transform(&self) -> &Matrix4 {
+        &self.transform
+    }
+
+    /// Sets the view transformation matrix of the camera
+    /// ## Panics
+    /// Will panic if the matrix is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.inverse = transform
+            .clone()
+            .inverse()
+            .expect("Camera transform should be invertible");
+        self.transform = transform;
+    }
+
+    /// Returns a ray starting at the camera and passing through the center
+    /// of the pixel at (px, py)
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
+        self.ray_for_point(px as f64 + 0.5, py as f64 + 0.5)
+    }
+
+    /// Returns a ray starting at the camera and passing through the point
+    /// (x, y) of the canvas, in pixel units from its top left corner. The
+    /// pixel at (px, py) covers the points from (px, py) to (px + 1, py + 1).
+    pub fn ray_for_point(&self, x: f64, y: f64) -> Ray {
+        // offset from the edge of the canvas to the point
+        let xoffset = x * self.pixel_size;
+        let yoffset = y * self.pixel_size;
+
+        // untransformed coordinates of the point in world space. The camera
+        // looks toward -z, so +x is to the *left*
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        let pixel = &self.inverse * &Tuple::new_point(world_x, world_y, -1.0);
+        let origin = &self.inverse * &Tuple::new_point(0.0, 0.0, 0.0);
+        let direction = (pixel - &origin).normalize();
+
+        Ray::new(origin, direction)
+    }
+
+    /// Renders the world into a canvas of hsize x vsize pixels, using one
+    /// worker thread per available CPU
+    pub fn render(&self, world: &World) -> Canvas {
+        self.render_with(world, &RenderOptions::new())
+    }
+
+    /// Renders the world into a canvas of hsize x vsize pixels, with the
+    /// given thread count and tile size. The image does not depend on the
+    /// number of threads.
+    pub fn render_with(&self, world: &World, options: &RenderOptions) -> Canvas {
+        render::render(self, world, options)
+    }
+
+    /// Renders the world like `render_with`, calling `on_progress` after
+    /// each completed tile. Setting `cancel` stops the render early: the
+    /// output then holds the tiles completed so far.
+    pub fn render_progressive<F>(
+        &self,
+        world: &World,
+        options: &RenderOptions,
+        cancel: &AtomicBool,
+        on_progress: F,
+    ) -> RenderOutput
+    where
+        F: FnMut(&Progress),
+    {
+        render::render_progressive(self, world, options, cancel, on_progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{draw::Color, transform, world};
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn constructing_a_camera() {
+        let camera = Camera::new(160, 120, PI / 2.0);
+
+        assert_eq!(camera.hsize(), 160);
+        assert_eq!(camera.vsize(), 120);
+        assert_eq!(camera.field_of_view(), PI / 2.0);
+        assert_eq!(*camera.transform(), Matrix4::identity());
+    }
+
+    #[test]
+    fn pixel_size_horizontal_canvas() {
+        let camera = Camera::new(200, 125, PI / 2.0);
+        assert!((camera.pixel_size() - 0.01).abs() < 1e-10);
+    }
+
+    #[test]
+    fn pixel_size_vertical_canvas() {
+        let camera = Camera::new(125, 200, PI / 2.0);
+        assert!((camera.pixel_size() - 0.01).abs() < 1e-10);
+    }
+
+    #[test]
+    fn ray_through_center_of_canvas() {
+        let camera = Camera::new(201, 101, PI / 2.0);
+        let ray = camera.ray_for_pixel(100, 50);
+
+        assert_eq!(ray.origin, Tuple::new_point(0.0, 0.0, 0.0));
+        assert_eq!(ray.direction, Tuple::new_vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn ray_through_corner_of_canvas() {
+        let camera = Camera::new(201, 101, PI / 2.0);
+        let ray = camera.ray_for_pixel(0, 0);
+
+        assert_eq!(ray.origin, Tuple::new_point(0.0, 0.0, 0.0));
+        assert_eq!(ray.direction, Tuple::new_vector(0.66519, 0.33259, -0.66851));
+    }
+
+    #[test]
+    fn ray_through_point_within_pixel() {
+        let camera = Camera::new(201, 101, PI / 2.0);
+
+        assert_eq!(
+            camera.ray_for_point(100.5, 50.5).direction,
+            camera.ray_for_pixel(100, 50).direction
+        );
+        // the top left corner of the canvas
+        let ray = camera.ray_for_point(0.0, 0.0);
+        let expected = Tuple::new_vector(1.0, 101.0 / 201.0, -1.0).normalize();
+        assert_eq!(ray.direction, expected);
+    }
+
+    #[test]
+    fn ray_when_camera_is_transformed() {
+        let mut camera = Camera::new(201, 101, PI / 2.0);
+        camera.set_transform(
+            transform::rotation_y(PI / 4.0) * transform::translation(0.0, -2.0, 5.0),
+        );
+        let ray = camera.ray_for_pixel(100, 50);
+
+        assert_eq!(ray.origin, Tuple::new_point(0.0, 2.0, -5.0));
+        assert_eq!(
+            ray.direction,
+            Tuple::new_vector(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    fn rendering_world_with_camera() {
+        let world = world::default_world();
+        let mut camera = Camera::new(11, 11, PI / 2.0);
+        let from = Tuple::new_point(0.0, 0.0, -5.0);
+        let to = Tuple::new_point(0.0, 0.0, 0.0);
+        let up = Tuple::new_vector(0.0, 1.0, 0.0);
+        camera.set_transform(transform::view_transform(&from, &to, &up));
+        let image = camera.render(&world);
+
+        assert_eq!(image.width(), 11);
+        assert_eq!(image.height(), 11);
+        assert_eq!(
+            *image.get_color_at(5, 5).unwrap(),
+            Color::new(0.38066, 0.47583, 0.2855)
+        );
+    }
+}
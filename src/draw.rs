@@ -1,5 +1,16 @@
 mod color;
 mod canvas;
+mod exr;
+mod hdr;
+mod png;
+mod ppm;
+#[cfg(test)]
+mod test_util;
+mod tonemap;
 
 pub use color::Color;
 pub use canvas::Canvas;
+pub use exr::ExrPixelType;
+pub use png::{PngBitDepth, PngColor, PngCompression, PngError, PngFilter, PngOptions};
+pub use ppm::{PpmError, PpmFormat};
+pub use tonemap::{Exposure, ToneMap, ToneMapOperator};
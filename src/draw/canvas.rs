@@ -1,5 +1,5 @@
-use crate::draw::Color;
-use std::{convert, fmt};
+use crate::draw::{Color, ToneMap};
+use std::fmt;
 
 #[derive(Debug, Clone, Copy)]
 struct ColorU8 {
@@ -13,6 +13,17 @@ impl ColorU8 {
     fn new(red: u8, green: u8, blue: u8) -> ColorU8 {
         ColorU8 { red, green, blue }
     }
+
+    /// Maps a color to 0..1 with the tone map, after scaling it by the
+    /// exposure of its canvas, and quantizes it
+    fn tone_mapped(color: &Color, tone_map: &ToneMap, scale: f64) -> ColorU8 {
+        let color = tone_map.map_color(color, scale);
+        ColorU8 {
+            red: (color.red * 255.0).round() as u8,
+            green: (color.green * 255.0).round() as u8,
+            blue: (color.blue * 255.0).round() as u8,
+        }
+    }
 }
 
 impl fmt::Display for ColorU8 {
@@ -21,16 +32,7 @@ impl fmt::Display for ColorU8 {
     }
 }
 
-impl convert::From<&Color> for ColorU8 {
-    fn from(value: &Color) -> Self {
-        ColorU8 {
-            red: (value.red.clamp(0.0, 1.0) * 255.0).round() as u8,
-            green: (value.green.clamp(0.0, 1.0) * 255.0).round() as u8,
-            blue: (value.blue.clamp(0.0, 1.0) * 255.0).round() as u8,
-        }
-    }
-}
-
+#[derive(Debug)]
 pub struct Canvas {
     width: usize,
     height: usize,
@@ -56,6 +58,14 @@ impl Canvas {
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
     pub fn size(&self) -> usize {
         self.width * self.height
     }
@@ -86,14 +96,21 @@ impl Canvas {
 
     /// Write the content of canvas to a PPM P3-formatted string
     pub fn to_ppm(&self) -> String {
+        self.to_ppm_with(&ToneMap::default())
+    }
+
+    /// Write the content of canvas to a PPM P3-formatted string, with colors
+    /// mapped to 0..1 by the given tone map
+    pub fn to_ppm_with(&self, tone_map: &ToneMap) -> String {
         const MAX_LINE_LEN: usize = 70;
+        let scale = tone_map.exposure_scale(self);
         let mut ppm = format!("P3\n{} {}\n255\n", self.width, self.height);
 
         for y in 0..self.height {
             let line = (0..self.width)
                 .map(|x| {
                     let color = self.get_color_at(x, y).unwrap();
-                    format!("{}", ColorU8::from(color))
+                    format!("{}", ColorU8::tone_mapped(color, tone_map, scale))
                 })
                 .collect::<Vec<_>>()
                 .join(" ");
@@ -230,7 +247,7 @@ mod tests {
             for j in 0..canvas_width {
                 canvas
                     .set_pixel_at(j, i, &color)
-                    .expect(&format!("Should be able to set color at ({},{})", j, i));
+                    .unwrap_or_else(|_| panic!("Should be able to set color at ({},{})", j, i));
             }
         }
 
@@ -254,7 +271,7 @@ mod tests {
             for j in 0..canvas_width {
                 canvas
                     .set_pixel_at(j, i, &color)
-                    .expect(&format!("Should be able to set color at ({},{})", j, i));
+                    .unwrap_or_else(|_| panic!("Should be able to set color at ({},{})", j, i));
             }
         }
 
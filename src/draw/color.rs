@@ -99,7 +99,7 @@ impl ops::Add<&Color> for Color {
     }
 }
 
-impl<'a, 'b> ops::Add<&'b Color> for &'a Color {
+impl<'b> ops::Add<&'b Color> for &Color {
     type Output = Color;
 
     fn add(self, rhs: &'b Color) -> Self::Output {
@@ -123,7 +123,7 @@ impl ops::Mul<&Color> for Color {
     }
 }
 
-impl<'a, 'b> ops::Mul<&'b Color> for &'a Color {
+impl<'b> ops::Mul<&'b Color> for &Color {
     type Output = Color;
 
     fn mul(self, rhs: &'b Color) -> Self::Output {
@@ -147,7 +147,7 @@ impl ops::Sub<&Color> for Color {
     }
 }
 
-impl<'a, 'b> ops::Sub<&'b Color> for &'a Color {
+impl<'b> ops::Sub<&'b Color> for &Color {
     type Output = Color;
 
     fn sub(self, rhs: &'b Color) -> Self::Output {
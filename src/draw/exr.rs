@@ -0,0 +1,354 @@
+use crate::draw::Canvas;
+use std::io::{self, Write};
+
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+
+/// Version 2, for a single part scanline image
+const VERSION: [u8; 4] = [2, 0, 0, 0];
+
+/// The channels of the image, in the alphabetical order required by the
+/// format
+const CHANNELS: [&str; 3] = ["B", "G", "R"];
+
+/// The type of the samples of an OpenEXR image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExrPixelType {
+    /// 16-bit floating point samples, the usual choice for color
+    Half,
+    /// 32-bit floating point samples
+    Float,
+}
+
+impl ExrPixelType {
+    fn size(self) -> usize {
+        match self {
+            ExrPixelType::Half => 2,
+            ExrPixelType::Float => 4,
+        }
+    }
+
+    /// Returns the code of the type in the channel list
+    fn code(self) -> i32 {
+        match self {
+            ExrPixelType::Half => 1,
+            ExrPixelType::Float => 2,
+        }
+    }
+}
+
+impl Canvas {
+    /// Streams the canvas to `writer` as an uncompressed scanline OpenEXR
+    /// image, one row at a time. Colors are written as they are, without
+    /// clamping to 0..1; half samples beyond 65504 become infinite.
+    /// ## Errors
+    /// Returns an error of kind `InvalidInput` if the canvas is empty or too
+    /// large for OpenEXR, and any error of the writer.
+    pub fn write_exr<W: Write>(&self, mut writer: W, pixel_type: ExrPixelType) -> io::Result<()> {
+        let row_size = self.width() * CHANNELS.len() * pixel_type.size();
+        let (Ok(x_max), Ok(y_max), Ok(row_size)) = (
+            i32::try_from(self.width()),
+            i32::try_from(self.height()),
+            i32::try_from(row_size),
+        ) else {
+            return Err(invalid_size());
+        };
+        if x_max == 0 || y_max == 0 {
+            return Err(invalid_size());
+        }
+        let window = [0, 0, x_max - 1, y_max - 1].map(i32::to_le_bytes).concat();
+
+        let mut channels = vec![];
+        for name in CHANNELS {
+            channels.extend_from_slice(name.as_bytes());
+            channels.push(0);
+            channels.extend_from_slice(&pixel_type.code().to_le_bytes());
+            // linear flag and reserved bytes, then no subsampling
+            channels.extend_from_slice(&[0; 4]);
+            channels.extend_from_slice(&1i32.to_le_bytes());
+            channels.extend_from_slice(&1i32.to_le_bytes());
+        }
+        channels.push(0);
+
+        let mut header = vec![];
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&VERSION);
+        attribute(&mut header, "channels", "chlist", &channels);
+        // no compression, and rows in increasing y order
+        attribute(&mut header, "compression", "compression", &[0]);
+        attribute(&mut header, "dataWindow", "box2i", &window);
+        attribute(&mut header, "displayWindow", "box2i", &window);
+        attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+        attribute(
+            &mut header,
+            "pixelAspectRatio",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+        attribute(
+            &mut header,
+            "screenWindowWidth",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        header.push(0);
+
+        // the table of offsets to each row, which all have the same size
+        let chunk_size = 8 + row_size as u64;
+        let first = (header.len() + 8 * self.height()) as u64;
+        for y in 0..self.height() as u64 {
+            header.extend_from_slice(&(first + y * chunk_size).to_le_bytes());
+        }
+        writer.write_all(&header)?;
+
+        let mut row = Vec::with_capacity(chunk_size as usize);
+        for y in 0..self.height() {
+            row.clear();
+            row.extend_from_slice(&(y as i32).to_le_bytes());
+            row.extend_from_slice(&row_size.to_le_bytes());
+            for channel in CHANNELS {
+                for x in 0..self.width() {
+                    let color = self
+                        .get_color_at(x, y)
+                        .expect("Pixel should be within the canvas");
+                    let value = match channel {
+                        "R" => color.red,
+                        "G" => color.green,
+                        _ => color.blue,
+                    };
+                    match pixel_type {
+                        ExrPixelType::Half => {
+                            row.extend_from_slice(&to_half(value).to_le_bytes());
+                        }
+                        ExrPixelType::Float => {
+                            row.extend_from_slice(&(value as f32).to_le_bytes());
+                        }
+                    }
+                }
+            }
+            writer.write_all(&row)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the content of canvas to an OpenEXR image with half samples
+    /// ## Panics
+    /// Will panic if the canvas is empty.
+    pub fn to_exr(&self) -> Vec<u8> {
+        let mut exr = vec![];
+        self.write_exr(&mut exr, ExrPixelType::Half)
+            .expect("Canvas should not be empty");
+        exr
+    }
+}
+
+fn invalid_size() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "OpenEXR images should be between 1 and 2^31 - 1 pixels wide and high",
+    )
+}
+
+/// Appends a header attribute: its name, its type, the size of its value and
+/// its value
+fn attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
+
+/// Converts a value to the bits of the nearest half precision float, with
+/// ties to even
+fn to_half(value: f64) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let mantissa = bits & 0x000f_ffff_ffff_ffff;
+
+    if exponent == 0x7ff {
+        // infinite, or NaN which stays NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let exponent = exponent - 1023 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        // subnormal, in units of 2^-24, from the mantissa with its implicit
+        // leading bit
+        if exponent < -20 {
+            return sign;
+        }
+        return sign | shift_rounding(mantissa | (1 << 52), (43 - exponent) as u32) as u16;
+    }
+    // the rounding may carry into the exponent, up to infinity
+    sign | shift_rounding(((exponent as u64) << 52) | mantissa, 42) as u16
+}
+
+/// Shifts a value right, rounding to the nearest result with ties to even
+fn shift_rounding(value: u64, shift: u32) -> u64 {
+    let result = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if remainder > half || (remainder == half && result & 1 == 1) {
+        result + 1
+    } else {
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::Color;
+
+    fn read_i32(data: &[u8], pos: usize) -> i32 {
+        i32::from_le_bytes(data[pos..pos + 4].try_into().unwrap())
+    }
+
+    fn from_half(bits: u16) -> f64 {
+        let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+        let exponent = ((bits >> 10) & 0x1f) as i32;
+        let mantissa = (bits & 0x3ff) as f64;
+        match exponent {
+            0 => sign * mantissa * 2f64.powi(-24),
+            0x1f if mantissa == 0.0 => sign * f64::INFINITY,
+            0x1f => f64::NAN,
+            _ => sign * (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+        }
+    }
+
+    fn canvas() -> Canvas {
+        let mut canvas = Canvas::new(3, 2);
+        canvas
+            .set_pixel_at(0, 0, &Color::new(1.0, 0.5, 0.25))
+            .unwrap();
+        canvas
+            .set_pixel_at(1, 0, &Color::new(12.5, 0.0, -2.0))
+            .unwrap();
+        canvas
+            .set_pixel_at(2, 1, &Color::new(0.1, 1000.0, 3.0))
+            .unwrap();
+        canvas
+    }
+
+    #[test]
+    fn converting_to_half() {
+        assert_eq!(to_half(0.0), 0x0000);
+        assert_eq!(to_half(-0.0), 0x8000);
+        assert_eq!(to_half(1.0), 0x3c00);
+        assert_eq!(to_half(0.5), 0x3800);
+        assert_eq!(to_half(-2.0), 0xc000);
+        assert_eq!(to_half(0.1), 0x2e66);
+        assert_eq!(to_half(65504.0), 0x7bff);
+        assert_eq!(to_half(65520.0), 0x7c00);
+        assert_eq!(to_half(1e10), 0x7c00);
+        assert_eq!(to_half(f64::NEG_INFINITY), 0xfc00);
+        assert_eq!(to_half(f64::NAN), 0x7e00);
+    }
+
+    #[test]
+    fn half_conversion_rounds_to_even() {
+        assert_eq!(to_half(1.0 + 2f64.powi(-11)), 0x3c00);
+        assert_eq!(to_half(1.0 + 3.0 * 2f64.powi(-11)), 0x3c02);
+        assert_eq!(to_half(1.0 + 2f64.powi(-11) + 2f64.powi(-20)), 0x3c01);
+        // the largest mantissa rounds up to the next exponent
+        assert_eq!(to_half(2.0 - 2f64.powi(-12)), 0x4000);
+    }
+
+    #[test]
+    fn converting_subnormals_to_half() {
+        assert_eq!(to_half(2f64.powi(-14)), 0x0400);
+        assert_eq!(to_half(2f64.powi(-15)), 0x0200);
+        assert_eq!(to_half(2f64.powi(-24)), 0x0001);
+        assert_eq!(to_half(2f64.powi(-25)), 0x0000);
+        assert_eq!(to_half(1.5 * 2f64.powi(-25)), 0x0001);
+        assert_eq!(to_half(-3.0 * 2f64.powi(-24)), 0x8003);
+        assert_eq!(to_half(1e-30), 0x0000);
+    }
+
+    #[test]
+    fn writing_exr_header() {
+        let exr = canvas().to_exr();
+
+        assert_eq!(exr[..8], [0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0]);
+        assert_eq!(&exr[8..24], b"channels\0chlist\0");
+        assert_eq!(read_i32(&exr, 24), 3 * 18 + 1);
+        assert_eq!(&exr[28..30], b"B\0");
+        assert_eq!(read_i32(&exr, 30), 1);
+
+        let window = b"dataWindow\0box2i\0\x10\0\0\0";
+        let pos = exr
+            .windows(window.len())
+            .position(|bytes| bytes == window)
+            .unwrap()
+            + window.len();
+        assert_eq!(
+            [0, 4, 8, 12].map(|offset| read_i32(&exr, pos + offset)),
+            [0, 0, 2, 1]
+        );
+        for name in [
+            &b"compression\0compression\0\x01\0\0\0\0"[..],
+            b"displayWindow\0box2i\0",
+            b"lineOrder\0lineOrder\0\x01\0\0\0\0",
+            b"pixelAspectRatio\0float\0",
+            b"screenWindowCenter\0v2f\0",
+            b"screenWindowWidth\0float\0",
+        ] {
+            assert!(exr.windows(name.len()).any(|bytes| bytes == name));
+        }
+    }
+
+    #[test]
+    fn writing_exr_rows() {
+        let canvas = canvas();
+        for pixel_type in [ExrPixelType::Half, ExrPixelType::Float] {
+            let mut exr = vec![];
+            canvas.write_exr(&mut exr, pixel_type).unwrap();
+            let row_size = 3 * 3 * pixel_type.size();
+
+            // the offset table follows the header, and the rows fill the
+            // rest of the file
+            let header_len = exr.len() - 2 * (8 + row_size) - 16;
+            assert_eq!(exr[header_len - 1], 0);
+            let offsets = [0, 8].map(|offset| {
+                u64::from_le_bytes(exr[header_len + offset..][..8].try_into().unwrap()) as usize
+            });
+            assert_eq!(offsets, [header_len + 16, header_len + 16 + 8 + row_size]);
+
+            for (y, offset) in offsets.into_iter().enumerate() {
+                assert_eq!(read_i32(&exr, offset), y as i32);
+                assert_eq!(read_i32(&exr, offset + 4), row_size as i32);
+                let samples: Vec<f64> = exr[offset + 8..offset + 8 + row_size]
+                    .chunks_exact(pixel_type.size())
+                    .map(|bytes| match pixel_type {
+                        ExrPixelType::Half => from_half(u16::from_le_bytes([bytes[0], bytes[1]])),
+                        ExrPixelType::Float => f32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+                    })
+                    .collect();
+
+                // blue, green and red samples of the row, without clamping
+                for x in 0..3 {
+                    let color = canvas.get_color_at(x, y).unwrap();
+                    for (channel, value) in
+                        [color.blue, color.green, color.red].into_iter().enumerate()
+                    {
+                        let sample = samples[channel * 3 + x];
+                        assert!((sample - value).abs() <= value.abs() / 1024.0 + 1e-7);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn writing_empty_canvas_fails() {
+        let error = Canvas::new(4, 0)
+            .write_exr(vec![], ExrPixelType::Half)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
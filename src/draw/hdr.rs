@@ -0,0 +1,288 @@
+use crate::draw::{Canvas, Color};
+use std::io::{self, Write};
+
+/// Narrowest and widest scanlines that can be run length encoded
+const MIN_RLE_WIDTH: usize = 8;
+const MAX_RLE_WIDTH: usize = 0x7fff;
+
+/// Shortest run worth encoding as a run rather than as literal bytes
+const MIN_RUN: usize = 4;
+
+/// Largest value that can be stored in RGBE, with a mantissa of 255 and an
+/// exponent of 127
+const MAX_RGBE: f64 = 255.0 / 256.0 * (1u128 << 127) as f64;
+
+impl Canvas {
+    /// Streams the canvas to `writer` as a Radiance HDR (RGBE) image, one
+    /// row at a time. Colors are written as they are, without clamping to
+    /// 0..1; negative and NaN components are written as zero.
+    ///
+    /// Rows are run length encoded, except for images narrower than 8 or
+    /// wider than 32767 pixels, which the format only stores flat.
+    /// ## Errors
+    /// Returns any error of the writer.
+    pub fn write_hdr<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(
+            writer,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            self.height(),
+            self.width()
+        )?;
+
+        let run_length = (MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&self.width());
+        let mut pixels = Vec::with_capacity(self.width());
+        let mut row = Vec::new();
+        for y in 0..self.height() {
+            pixels.clear();
+            pixels.extend((0..self.width()).map(|x| {
+                to_rgbe(
+                    self.get_color_at(x, y)
+                        .expect("Pixel should be within the canvas"),
+                )
+            }));
+
+            row.clear();
+            if run_length {
+                // a flat pixel can never start with 2, 2 and a byte below
+                // 128, since its largest component is at least 128, which
+                // tells readers that the row is encoded
+                row.extend_from_slice(&[2, 2, (self.width() >> 8) as u8, self.width() as u8]);
+                let mut component = Vec::with_capacity(self.width());
+                for i in 0..4 {
+                    component.clear();
+                    component.extend(pixels.iter().map(|pixel| pixel[i]));
+                    run_length_encode(&mut row, &component);
+                }
+            } else {
+                row.extend(pixels.iter().flatten());
+            }
+            writer.write_all(&row)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the content of canvas to a Radiance HDR image
+    pub fn to_hdr(&self) -> Vec<u8> {
+        let mut hdr = vec![];
+        self.write_hdr(&mut hdr)
+            .expect("Writing to a vector should not fail");
+        hdr
+    }
+}
+
+/// Encodes a color as a shared exponent and a mantissa for each component
+fn to_rgbe(color: &Color) -> [u8; 4] {
+    // comparisons are false for NaN, which also becomes zero
+    let clamp = |value: f64| {
+        if value > 0.0 {
+            value.min(MAX_RGBE)
+        } else {
+            0.0
+        }
+    };
+    let (red, green, blue) = (clamp(color.red), clamp(color.green), clamp(color.blue));
+    let max = red.max(green).max(blue);
+    if max < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    // max = mantissa * 2^exponent, with the mantissa in 0.5..1
+    let exponent = ((max.to_bits() >> 52) & 0x7ff) as i32 - 1022;
+    let scale = 256.0 / 2f64.powi(exponent);
+    [
+        (red * scale) as u8,
+        (green * scale) as u8,
+        (blue * scale) as u8,
+        (exponent + 128) as u8,
+    ]
+}
+
+/// Appends the bytes of one component of a row, as runs of a repeated byte
+/// (a count above 128 followed by the byte) and literal bytes (a count up to
+/// 128 followed by as many bytes)
+fn run_length_encode(output: &mut Vec<u8>, data: &[u8]) {
+    let mut pos = 0;
+    while pos < data.len() {
+        // find the next run long enough to be worth encoding
+        let mut run_start = pos;
+        let mut run_len = 0;
+        while run_start < data.len() {
+            run_len = data[run_start..]
+                .iter()
+                .take(127)
+                .take_while(|&&byte| byte == data[run_start])
+                .count();
+            if run_len >= MIN_RUN {
+                break;
+            }
+            run_start += run_len;
+        }
+
+        for literals in data[pos..run_start].chunks(128) {
+            output.push(literals.len() as u8);
+            output.extend_from_slice(literals);
+        }
+        if run_start == data.len() {
+            break;
+        }
+        output.extend_from_slice(&[128 + run_len as u8, data[run_start]]);
+        pos = run_start + run_len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes an image written by `write_hdr`, returning its size and the
+    /// RGBE bytes of its pixels
+    fn decode(hdr: &[u8]) -> (usize, usize, Vec<[u8; 4]>) {
+        let text = String::from_utf8_lossy(hdr);
+        let header_end = text.find("\n\n").unwrap() + 2;
+        let resolution_end = header_end + text[header_end..].find('\n').unwrap();
+        let resolution: Vec<&str> = text[header_end..resolution_end].split(' ').collect();
+        assert_eq!((resolution[0], resolution[2]), ("-Y", "+X"));
+        let height: usize = resolution[1].parse().unwrap();
+        let width: usize = resolution[3].parse().unwrap();
+
+        let mut data = hdr[resolution_end + 1..].iter().copied();
+        let mut pixels = vec![];
+        for _ in 0..height {
+            if !(MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&width) {
+                for _ in 0..width {
+                    pixels.push([0; 4].map(|_| data.next().unwrap()));
+                }
+                continue;
+            }
+
+            let start = [0; 4].map(|_| data.next().unwrap());
+            assert_eq!(start, [2, 2, (width >> 8) as u8, width as u8]);
+            let mut components = [vec![], vec![], vec![], vec![]];
+            for component in &mut components {
+                while component.len() < width {
+                    let count = data.next().unwrap() as usize;
+                    if count > 128 {
+                        let byte = data.next().unwrap();
+                        component.extend(std::iter::repeat_n(byte, count - 128));
+                    } else {
+                        component.extend(data.by_ref().take(count));
+                    }
+                }
+                assert_eq!(component.len(), width);
+            }
+            pixels.extend((0..width).map(|x| components.each_ref().map(|component| component[x])));
+        }
+        assert_eq!(data.next(), None);
+        (width, height, pixels)
+    }
+
+    fn from_rgbe([red, green, blue, exponent]: [u8; 4]) -> Color {
+        if exponent == 0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let scale = 2f64.powi(exponent as i32 - 136);
+        Color::new(
+            (red as f64 + 0.5) * scale,
+            (green as f64 + 0.5) * scale,
+            (blue as f64 + 0.5) * scale,
+        )
+    }
+
+    #[test]
+    fn encoding_colors() {
+        assert_eq!(to_rgbe(&Color::new(1.0, 1.0, 1.0)), [128, 128, 128, 129]);
+        assert_eq!(to_rgbe(&Color::new(0.5, 0.25, 0.0)), [128, 64, 0, 128]);
+        assert_eq!(to_rgbe(&Color::new(3.0, 0.75, 1.5)), [192, 48, 96, 130]);
+        assert_eq!(to_rgbe(&Color::new(0.0, 0.0, 0.0)), [0, 0, 0, 0]);
+        assert_eq!(to_rgbe(&Color::new(-1.0, f64::NAN, 2.0)), [0, 0, 128, 130]);
+        assert_eq!(
+            to_rgbe(&Color::new(f64::INFINITY, 0.0, 0.0)),
+            [255, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn encoded_colors_keep_their_range() {
+        for value in [1e-20, 0.001, 0.3, 1.0, 7.5, 1234.5, 1e20] {
+            let color = from_rgbe(to_rgbe(&Color::new(value, value / 3.0, value * 0.9)));
+            assert!((color.red - value).abs() <= value / 128.0);
+            assert!((color.green - value / 3.0).abs() <= value / 128.0);
+            assert!((color.blue - value * 0.9).abs() <= value / 128.0);
+        }
+    }
+
+    #[test]
+    fn encoding_runs_and_literals() {
+        let mut output = vec![];
+        run_length_encode(&mut output, &[1, 2, 3, 3, 3, 3, 3, 4, 4]);
+        assert_eq!(output, [2, 1, 2, 128 + 5, 3, 2, 4, 4]);
+
+        // runs and literals are split at their largest lengths
+        let mut output = vec![];
+        run_length_encode(&mut output, &[7; 300]);
+        assert_eq!(output, [255, 7, 255, 7, 128 + 46, 7]);
+
+        let data: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let mut output = vec![];
+        run_length_encode(&mut output, &data);
+        assert_eq!(output[0], 128);
+        assert_eq!(output[129], 72);
+        assert_eq!(output.len(), 202);
+    }
+
+    #[test]
+    fn writing_hdr_header() {
+        let hdr = Canvas::new(5, 3).to_hdr();
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 3 +X 5\n";
+
+        assert_eq!(&hdr[..header.len()], header);
+        // narrow images are written flat
+        assert_eq!(hdr.len(), header.len() + 5 * 3 * 4);
+    }
+
+    #[test]
+    fn round_trip_keeps_bright_colors() {
+        for (width, height) in [(5, 3), (8, 2), (40, 7)] {
+            let mut canvas = Canvas::new(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    // flat areas on the left, and varied colors on the right
+                    let color = if x < width / 2 {
+                        Color::new(4.0, 2.0, 0.5)
+                    } else {
+                        Color::new(x as f64 * 1.5, y as f64 / 10.0, 100.0 / (x + y + 1) as f64)
+                    };
+                    canvas.set_pixel_at(x, y, &color).unwrap();
+                }
+            }
+
+            let (read_width, read_height, pixels) = decode(&canvas.to_hdr());
+            assert_eq!((read_width, read_height), (width, height));
+            for (i, &pixel) in pixels.iter().enumerate() {
+                let expected = canvas.get_color_at(i % width, i / width).unwrap();
+                let color = from_rgbe(pixel);
+                let tolerance = expected.red.max(expected.green).max(expected.blue) / 128.0;
+                assert!((color.red - expected.red).abs() <= tolerance);
+                assert!((color.green - expected.green).abs() <= tolerance);
+                assert!((color.blue - expected.blue).abs() <= tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn flat_rows_are_compressed() {
+        let mut canvas = Canvas::new(100, 10);
+        for y in 0..10 {
+            for x in 0..100 {
+                canvas
+                    .set_pixel_at(x, y, &Color::new(10.0, 5.0, 1.0))
+                    .unwrap();
+            }
+        }
+        let hdr = canvas.to_hdr();
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 10 +X 100\n";
+
+        // every component of a row is a single run
+        assert_eq!(hdr.len(), header.len() + 10 * (4 + 4 * 2));
+    }
+}
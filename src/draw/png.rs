@@ -0,0 +1,982 @@
+mod zlib;
+
+pub use zlib::Compression as PngCompression;
+
+use super::ppm::quantize;
+use crate::draw::{Canvas, Color, ToneMap};
+use std::{
+    error, fmt,
+    io::{self, Read, Write},
+};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Table of the CRC-32 of every byte value, for the polynomial used by PNG
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+/// Error raised while reading a PNG image
+#[derive(Debug, Clone, PartialEq)]
+pub enum PngError {
+    /// The data does not start with the PNG signature
+    InvalidSignature,
+    /// The data ends in the middle of a chunk, or before the image end
+    UnexpectedEnd,
+    /// The checksum of a chunk does not match its content
+    CrcMismatch { chunk: String },
+    /// A required chunk is missing, or chunks are in the wrong order
+    MissingChunk { chunk: &'static str },
+    /// The header describes an impossible image
+    InvalidHeader { reason: &'static str },
+    /// The image uses a feature this decoder does not support
+    Unsupported { feature: String },
+    /// The compressed image data is corrupted
+    InvalidData { reason: &'static str },
+    /// A scanline starts with an unknown filter type
+    InvalidFilter { row: usize, filter: u8 },
+    /// A palette index is outside of the palette
+    InvalidPaletteIndex { index: u8 },
+    /// The data could not be read
+    Io { kind: io::ErrorKind },
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PngError::InvalidSignature => write!(f, "not a PNG image"),
+            PngError::UnexpectedEnd => write!(f, "unexpected end of data"),
+            PngError::CrcMismatch { chunk } => write!(f, "{chunk} chunk: checksum mismatch"),
+            PngError::MissingChunk { chunk } => write!(f, "missing {chunk} chunk"),
+            PngError::InvalidHeader { reason } => write!(f, "invalid header: {reason}"),
+            PngError::Unsupported { feature } => write!(f, "unsupported {feature}"),
+            PngError::InvalidData { reason } => write!(f, "invalid image data: {reason}"),
+            PngError::InvalidFilter { row, filter } => {
+                write!(f, "row {row}: invalid filter type {filter}")
+            }
+            PngError::InvalidPaletteIndex { index } => {
+                write!(f, "palette index {index} is out of range")
+            }
+            PngError::Io { kind } => write!(f, "{kind}"),
+        }
+    }
+}
+
+impl error::Error for PngError {}
+
+/// The channels written for each pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PngColor {
+    Rgb,
+    /// RGB, with an opaque alpha channel
+    Rgba,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PngBitDepth {
+    Eight,
+    Sixteen,
+}
+
+/// The filter applied to scanlines before compression, to make them easier
+/// to compress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PngFilter {
+    None,
+    /// Difference with the pixel on the left
+    Sub,
+    /// Difference with the pixel above
+    Up,
+    /// Difference with the average of the pixels on the left and above
+    Average,
+    /// Difference with the Paeth predictor of the neighboring pixels
+    Paeth,
+    /// The filter giving the smallest sum of absolute differences, chosen
+    /// for each scanline
+    Adaptive,
+}
+
+/// Settings for writing PNG images
+#[derive(Debug, Clone, PartialEq)]
+pub struct PngOptions {
+    pub color: PngColor,
+    pub bit_depth: PngBitDepth,
+    pub compression: PngCompression,
+    pub filter: PngFilter,
+    /// Maps colors to 0..1 before they are quantized
+    pub tone_map: ToneMap,
+}
+
+impl PngOptions {
+    /// Creates options for compressed 8-bit RGB images, with colors clamped
+    /// to 0..1
+    pub fn new() -> Self {
+        Self {
+            color: PngColor::Rgb,
+            bit_depth: PngBitDepth::Eight,
+            compression: PngCompression::FixedHuffman,
+            filter: PngFilter::Adaptive,
+            tone_map: ToneMap::default(),
+        }
+    }
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The content of an IHDR chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Header {
+    width: usize,
+    height: usize,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            0 | 3 => 1,
+            4 => 2,
+            2 => 3,
+            _ => 4,
+        }
+    }
+
+    /// Returns the number of bytes of a scanline, without its filter type,
+    /// or `None` if it overflows
+    fn stride(&self) -> Option<usize> {
+        let bits = self
+            .width
+            .checked_mul(self.channels() * self.bit_depth as usize)?;
+        Some(bits.div_ceil(8))
+    }
+
+    /// Returns the number of bytes of the scanlines, with their filter
+    /// types, or `None` if it overflows
+    fn data_size(&self) -> Option<usize> {
+        self.stride()?.checked_add(1)?.checked_mul(self.height)
+    }
+
+    /// Returns the distance in bytes between a byte and the matching byte of
+    /// the previous pixel, as used by the filters (at least one)
+    fn filter_distance(&self) -> usize {
+        (self.channels() * self.bit_depth as usize).div_ceil(8)
+    }
+}
+
+impl Canvas {
+    /// Writes the canvas as a non-interlaced PNG image. Colors are mapped to
+    /// 0..1 by the tone map of the options; the alpha channel, if any, is
+    /// fully opaque.
+    /// ## Errors
+    /// Returns an error of kind `InvalidInput` if the canvas is empty or too
+    /// large for PNG, and any error of the writer.
+    pub fn write_png<W: Write>(&self, mut writer: W, options: &PngOptions) -> io::Result<()> {
+        let (Ok(width), Ok(height)) = (u32::try_from(self.width()), u32::try_from(self.height()))
+        else {
+            return Err(invalid_size());
+        };
+        if width == 0 || height == 0 || width > i32::MAX as u32 || height > i32::MAX as u32 {
+            return Err(invalid_size());
+        }
+        let header = Header {
+            width: self.width(),
+            height: self.height(),
+            bit_depth: match options.bit_depth {
+                PngBitDepth::Eight => 8,
+                PngBitDepth::Sixteen => 16,
+            },
+            color_type: match options.color {
+                PngColor::Rgb => 2,
+                PngColor::Rgba => 6,
+            },
+        };
+        if header.data_size().is_none() {
+            return Err(invalid_size());
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        // no compression method, filter method or interlacing to choose
+        ihdr.extend_from_slice(&[header.bit_depth, header.color_type, 0, 0, 0]);
+
+        let scanlines = self.filtered_scanlines(&header, options);
+        let idat = zlib::compress(&scanlines, options.compression);
+
+        writer.write_all(&SIGNATURE)?;
+        write_chunk(&mut writer, b"IHDR", &ihdr)?;
+        write_chunk(&mut writer, b"IDAT", &idat)?;
+        write_chunk(&mut writer, b"IEND", &[])?;
+        writer.flush()
+    }
+
+    /// Writes the content of canvas to an 8-bit RGB PNG image
+    /// ## Panics
+    /// Will panic if the canvas is empty.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = vec![];
+        self.write_png(&mut png, &PngOptions::new())
+            .expect("Canvas should not be empty");
+        png
+    }
+
+    /// Reads a non-interlaced PNG image, of any color type and bit depth.
+    /// Samples are scaled to 0..1, grayscale images give gray colors, and
+    /// the alpha channel is ignored.
+    pub fn read_png<R: Read>(mut reader: R) -> Result<Canvas, PngError> {
+        let mut data = vec![];
+        reader
+            .read_to_end(&mut data)
+            .map_err(|error| PngError::Io { kind: error.kind() })?;
+
+        let (header, palette, idat) = read_chunks(&data)?;
+        let size = header.data_size().ok_or_else(image_too_large)?;
+        let scanlines =
+            zlib::decompress(&idat, size).map_err(|reason| PngError::InvalidData { reason })?;
+        let rows = unfilter(&header, scanlines)?;
+
+        let max = ((1u32 << header.bit_depth) - 1) as f64;
+        let mut canvas = Canvas::new(header.width, header.height);
+        let mut samples = Vec::with_capacity(header.width * header.channels());
+        for (y, row) in rows.chunks_exact(rows.len() / header.height).enumerate() {
+            samples.clear();
+            unpack_samples(
+                row,
+                header.bit_depth,
+                header.width * header.channels(),
+                &mut samples,
+            );
+
+            for (x, pixel) in samples.chunks_exact(header.channels()).enumerate() {
+                let color = match header.color_type {
+                    3 => {
+                        let index = pixel[0] as usize;
+                        let entry = palette.get(index * 3..index * 3 + 3).ok_or(
+                            PngError::InvalidPaletteIndex {
+                                index: pixel[0] as u8,
+                            },
+                        )?;
+                        Color::new(
+                            entry[0] as f64 / 255.0,
+                            entry[1] as f64 / 255.0,
+                            entry[2] as f64 / 255.0,
+                        )
+                    }
+                    0 | 4 => {
+                        let gray = pixel[0] as f64 / max;
+                        Color::new(gray, gray, gray)
+                    }
+                    _ => Color::new(
+                        pixel[0] as f64 / max,
+                        pixel[1] as f64 / max,
+                        pixel[2] as f64 / max,
+                    ),
+                };
+                canvas
+                    .set_pixel_at(x, y, &color)
+                    .expect("Pixel should be within the canvas");
+            }
+        }
+        Ok(canvas)
+    }
+
+    /// Returns the scanlines of the image, each preceded by its filter type
+    fn filtered_scanlines(&self, header: &Header, options: &PngOptions) -> Vec<u8> {
+        let scale = options.tone_map.exposure_scale(self);
+        let stride = header.stride().expect("Size should have been checked");
+        let distance = header.filter_distance();
+        let maxval = if header.bit_depth == 16 { 65535 } else { 255 };
+        let mut output = Vec::with_capacity((stride + 1) * header.height);
+        let mut previous = vec![0; stride];
+        let mut current = Vec::with_capacity(stride);
+        let mut candidate = vec![0; stride];
+        let mut best = vec![0; stride];
+
+        for y in 0..header.height {
+            current.clear();
+            for x in 0..header.width {
+                let color = self
+                    .get_color_at(x, y)
+                    .expect("Pixel should be within the canvas");
+                let color = options.tone_map.map_color(color, scale);
+                let samples = [color.red, color.green, color.blue, 1.0];
+                for &value in &samples[..header.channels()] {
+                    let sample = quantize(value, maxval);
+                    if maxval > 255 {
+                        current.extend_from_slice(&sample.to_be_bytes());
+                    } else {
+                        current.push(sample as u8);
+                    }
+                }
+            }
+
+            let filter_type = match options.filter {
+                PngFilter::Adaptive => {
+                    let mut best_type = 0;
+                    let mut best_cost = u64::MAX;
+                    for filter_type in 0..5 {
+                        apply_filter(filter_type, &current, &previous, distance, &mut candidate);
+                        // bytes are seen as signed, so that small negative
+                        // differences are cheap too
+                        let cost = candidate
+                            .iter()
+                            .map(|&byte| (byte as i8).unsigned_abs() as u64)
+                            .sum();
+                        if cost < best_cost {
+                            best_cost = cost;
+                            best_type = filter_type;
+                            best.copy_from_slice(&candidate);
+                        }
+                    }
+                    best_type
+                }
+                _ => {
+                    let filter_type = match options.filter {
+                        PngFilter::Sub => 1,
+                        PngFilter::Up => 2,
+                        PngFilter::Average => 3,
+                        PngFilter::Paeth => 4,
+                        _ => 0,
+                    };
+                    apply_filter(filter_type, &current, &previous, distance, &mut best);
+                    filter_type
+                }
+            };
+
+            output.push(filter_type);
+            output.extend_from_slice(&best);
+            std::mem::swap(&mut previous, &mut current);
+        }
+        output
+    }
+}
+
+fn invalid_size() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "PNG images should be between 1 and 2^31 - 1 pixels wide and high",
+    )
+}
+
+fn crc32(chunk_type: &[u8], data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in chunk_type.iter().chain(data) {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xffff_ffff
+}
+
+fn write_chunk<W: Write>(writer: &mut W, chunk_type: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "PNG chunk is too large"))?;
+    writer.write_all(&length.to_be_bytes())?;
+    writer.write_all(chunk_type)?;
+    writer.write_all(data)?;
+    writer.write_all(&crc32(chunk_type, data).to_be_bytes())
+}
+
+/// Reads the chunks of an image, returning its header, its palette (if any)
+/// and the concatenation of its IDAT chunks
+fn read_chunks(data: &[u8]) -> Result<(Header, Vec<u8>, Vec<u8>), PngError> {
+    if data.len() < SIGNATURE.len() || data[..SIGNATURE.len()] != SIGNATURE {
+        return Err(PngError::InvalidSignature);
+    }
+    let mut pos = SIGNATURE.len();
+    let mut header = None;
+    let mut palette = vec![];
+    let mut idat = vec![];
+
+    loop {
+        let length = data.get(pos..pos + 4).ok_or(PngError::UnexpectedEnd)?;
+        let length = u32::from_be_bytes([length[0], length[1], length[2], length[3]]) as usize;
+        let chunk_type = data.get(pos + 4..pos + 8).ok_or(PngError::UnexpectedEnd)?;
+        let content = data
+            .get(pos + 8..pos + 8 + length)
+            .ok_or(PngError::UnexpectedEnd)?;
+        let crc = data
+            .get(pos + 8 + length..pos + 12 + length)
+            .ok_or(PngError::UnexpectedEnd)?;
+        pos += 12 + length;
+
+        let name = String::from_utf8_lossy(chunk_type).into_owned();
+        if u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]) != crc32(chunk_type, content) {
+            return Err(PngError::CrcMismatch { chunk: name });
+        }
+        if header.is_none() && chunk_type != b"IHDR" {
+            return Err(PngError::MissingChunk { chunk: "IHDR" });
+        }
+
+        match chunk_type {
+            b"IHDR" => header = Some(read_header(content)?),
+            b"PLTE" => palette = content.to_vec(),
+            b"IDAT" => idat.extend_from_slice(content),
+            b"IEND" => break,
+            // ancillary chunks have a lowercase first letter, and may be
+            // skipped
+            _ if chunk_type[0].is_ascii_lowercase() => {}
+            _ => {
+                return Err(PngError::Unsupported {
+                    feature: format!("{name} chunk"),
+                });
+            }
+        }
+    }
+
+    let header = header.ok_or(PngError::MissingChunk { chunk: "IHDR" })?;
+    if idat.is_empty() {
+        return Err(PngError::MissingChunk { chunk: "IDAT" });
+    }
+    if header.color_type == 3 && palette.is_empty() {
+        return Err(PngError::MissingChunk { chunk: "PLTE" });
+    }
+    Ok((header, palette, idat))
+}
+
+fn read_header(content: &[u8]) -> Result<Header, PngError> {
+    let [
+        w0,
+        w1,
+        w2,
+        w3,
+        h0,
+        h1,
+        h2,
+        h3,
+        bit_depth,
+        color_type,
+        compression,
+        filter,
+        interlace,
+    ] = *content
+    else {
+        return Err(PngError::InvalidHeader {
+            reason: "IHDR chunk should be 13 bytes long",
+        });
+    };
+    let width = u32::from_be_bytes([w0, w1, w2, w3]) as usize;
+    let height = u32::from_be_bytes([h0, h1, h2, h3]) as usize;
+
+    if width == 0 || height == 0 {
+        return Err(PngError::InvalidHeader {
+            reason: "image is empty",
+        });
+    }
+    // the same limit as the writer, from the PNG specification
+    if width > i32::MAX as usize || height > i32::MAX as usize {
+        return Err(PngError::InvalidHeader {
+            reason: "image is wider or higher than 2^31 - 1 pixels",
+        });
+    }
+    let valid_depth = match color_type {
+        0 => matches!(bit_depth, 1 | 2 | 4 | 8 | 16),
+        3 => matches!(bit_depth, 1 | 2 | 4 | 8),
+        2 | 4 | 6 => matches!(bit_depth, 8 | 16),
+        _ => {
+            return Err(PngError::InvalidHeader {
+                reason: "unknown color type",
+            });
+        }
+    };
+    if !valid_depth {
+        return Err(PngError::InvalidHeader {
+            reason: "bit depth is not allowed for the color type",
+        });
+    }
+    if compression != 0 || filter != 0 {
+        return Err(PngError::InvalidHeader {
+            reason: "unknown compression or filter method",
+        });
+    }
+    if interlace != 0 {
+        return Err(PngError::Unsupported {
+            feature: "interlaced image".to_string(),
+        });
+    }
+
+    let header = Header {
+        width,
+        height,
+        bit_depth,
+        color_type,
+    };
+    if header.data_size().is_none() {
+        return Err(image_too_large());
+    }
+    Ok(header)
+}
+
+fn image_too_large() -> PngError {
+    PngError::InvalidHeader {
+        reason: "image is too large for this platform",
+    }
+}
+
+/// The predictor of the Paeth filter: whichever of the left, above and upper
+/// left bytes is closest to left + above - upper left
+fn paeth(left: u8, above: u8, upper_left: u8) -> u8 {
+    let estimate = left as i16 + above as i16 - upper_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_above = (estimate - above as i16).abs();
+    let distance_upper_left = (estimate - upper_left as i16).abs();
+
+    if distance_left <= distance_above && distance_left <= distance_upper_left {
+        left
+    } else if distance_above <= distance_upper_left {
+        above
+    } else {
+        upper_left
+    }
+}
+
+/// Returns the prediction of a filter for the byte at index `i`, from the
+/// already known bytes of the current and previous scanlines
+fn predict(filter_type: u8, current: &[u8], previous: &[u8], distance: usize, i: usize) -> u8 {
+    let left = if i >= distance {
+        current[i - distance]
+    } else {
+        0
+    };
+    let above = previous[i];
+    let upper_left = if i >= distance {
+        previous[i - distance]
+    } else {
+        0
+    };
+
+    match filter_type {
+        1 => left,
+        2 => above,
+        3 => ((left as u16 + above as u16) / 2) as u8,
+        4 => paeth(left, above, upper_left),
+        _ => 0,
+    }
+}
+
+fn apply_filter(
+    filter_type: u8,
+    current: &[u8],
+    previous: &[u8],
+    distance: usize,
+    output: &mut [u8],
+) {
+    for i in 0..current.len() {
+        output[i] = current[i].wrapping_sub(predict(filter_type, current, previous, distance, i));
+    }
+}
+
+/// Reverses the filters of the scanlines, returning the raw rows one after
+/// the other
+fn unfilter(header: &Header, scanlines: Vec<u8>) -> Result<Vec<u8>, PngError> {
+    let (Some(stride), Some(size)) = (header.stride(), header.data_size()) else {
+        return Err(image_too_large());
+    };
+    let distance = header.filter_distance();
+    if scanlines.len() < size {
+        return Err(PngError::UnexpectedEnd);
+    }
+
+    let mut rows = vec![0; stride * header.height];
+    let zero_row = vec![0; stride];
+    for (y, scanline) in scanlines
+        .chunks_exact(stride + 1)
+        .take(header.height)
+        .enumerate()
+    {
+        let filter_type = scanline[0];
+        if filter_type > 4 {
+            return Err(PngError::InvalidFilter {
+                row: y,
+                filter: filter_type,
+            });
+        }
+
+        let (done, rest) = rows.split_at_mut(y * stride);
+        let previous = if y == 0 {
+            &zero_row[..]
+        } else {
+            &done[(y - 1) * stride..]
+        };
+        let current = &mut rest[..stride];
+        for i in 0..stride {
+            current[i] =
+                scanline[i + 1].wrapping_add(predict(filter_type, current, previous, distance, i));
+        }
+    }
+    Ok(rows)
+}
+
+/// Splits a row into `count` samples of the given bit depth
+fn unpack_samples(row: &[u8], bit_depth: u8, count: usize, samples: &mut Vec<u16>) {
+    match bit_depth {
+        16 => samples.extend(
+            row.chunks_exact(2)
+                .take(count)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]])),
+        ),
+        8 => samples.extend(row.iter().take(count).map(|&byte| byte as u16)),
+        _ => {
+            // samples smaller than a byte are packed from the most
+            // significant bit
+            let per_byte = 8 / bit_depth as usize;
+            let mask = (1u8 << bit_depth) - 1;
+            samples.extend((0..count).map(|i| {
+                let shift = 8 - bit_depth as usize * (i % per_byte + 1);
+                ((row[i / per_byte] >> shift) & mask) as u16
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::test_util::{assert_same_image, gradient};
+
+    /// Builds an image from its header fields and unfiltered scanlines
+    fn build_png(
+        width: u32,
+        height: u32,
+        bit_depth: u8,
+        color_type: u8,
+        scanlines: &[u8],
+    ) -> Vec<u8> {
+        let mut ihdr = vec![];
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &ihdr).unwrap();
+        write_chunk(
+            &mut png,
+            b"IDAT",
+            &zlib::compress(scanlines, PngCompression::Stored),
+        )
+        .unwrap();
+        write_chunk(&mut png, b"IEND", &[]).unwrap();
+        png
+    }
+
+    #[test]
+    fn crc_of_chunks() {
+        assert_eq!(crc32(b"IEND", &[]), 0xae42_6082);
+        assert_eq!(crc32(b"", b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn paeth_predictor() {
+        assert_eq!(paeth(10, 20, 10), 20);
+        assert_eq!(paeth(20, 10, 10), 20);
+        assert_eq!(paeth(10, 20, 30), 10);
+        assert_eq!(paeth(50, 60, 55), 55);
+        assert_eq!(paeth(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn writing_png_header() {
+        let png = Canvas::new(3, 2).to_png();
+
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[8..16], b"\0\0\0\x0dIHDR");
+        assert_eq!(&png[16..29], &[0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+    }
+
+    #[test]
+    fn round_trip_through_every_option() {
+        let canvas = gradient(13, 9, 0.7);
+
+        for color in [PngColor::Rgb, PngColor::Rgba] {
+            for (bit_depth, maxval) in
+                [(PngBitDepth::Eight, 255.0), (PngBitDepth::Sixteen, 65535.0)]
+            {
+                for compression in [PngCompression::Stored, PngCompression::FixedHuffman] {
+                    for filter in [
+                        PngFilter::None,
+                        PngFilter::Sub,
+                        PngFilter::Up,
+                        PngFilter::Average,
+                        PngFilter::Paeth,
+                        PngFilter::Adaptive,
+                    ] {
+                        let options = PngOptions {
+                            color,
+                            bit_depth,
+                            compression,
+                            filter,
+                            tone_map: ToneMap::default(),
+                        };
+                        let mut png = vec![];
+                        canvas.write_png(&mut png, &options).unwrap();
+                        let read = Canvas::read_png(png.as_slice()).unwrap();
+                        assert_same_image(&canvas, &read, maxval);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn colors_are_clamped() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas
+            .set_pixel_at(0, 0, &Color::new(-0.5, 0.5, 1.5))
+            .unwrap();
+        let read = Canvas::read_png(canvas.to_png().as_slice()).unwrap();
+
+        assert_eq!(
+            *read.get_color_at(0, 0).unwrap(),
+            Color::new(0.0, 128.0 / 255.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn flat_images_are_compressed() {
+        let mut canvas = Canvas::new(64, 64);
+        for y in 0..64 {
+            for x in 0..64 {
+                canvas
+                    .set_pixel_at(x, y, &Color::new(0.2, 0.4, 0.6))
+                    .unwrap();
+            }
+        }
+        assert!(canvas.to_png().len() < 64 * 64 * 3 / 20);
+    }
+
+    #[test]
+    fn writing_empty_canvas_fails() {
+        let error = Canvas::new(0, 5)
+            .write_png(vec![], &PngOptions::new())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn reading_palette_image() {
+        // 3x2 pixels, 2-bit palette indices, with a text chunk, compressed
+        // by zlib
+        let png = [
+            0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02, 0x02, 0x03, 0x00, 0x00,
+            0x00, 0xe0, 0x1a, 0x8e, 0x89, 0x00, 0x00, 0x00, 0x0c, 0x50, 0x4c, 0x54, 0x45, 0xff,
+            0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xfb, 0x00, 0x60,
+            0xf6, 0x00, 0x00, 0x00, 0x14, 0x74, 0x45, 0x58, 0x74, 0x43, 0x6f, 0x6d, 0x6d, 0x65,
+            0x6e, 0x74, 0x00, 0x6d, 0x61, 0x64, 0x65, 0x20, 0x62, 0x79, 0x20, 0x68, 0x61, 0x6e,
+            0x64, 0x2c, 0xa8, 0xfa, 0x29, 0x00, 0x00, 0x00, 0x0c, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0xda, 0x63, 0x90, 0x60, 0x78, 0x02, 0x00, 0x01, 0x30, 0x00, 0xfd, 0x68, 0x30, 0xcf,
+            0xdf, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+        ];
+        let canvas = Canvas::read_png(&png[..]).unwrap();
+        let (red, green, blue) = (
+            Color::new(1.0, 0.0, 0.0),
+            Color::new(0.0, 1.0, 0.0),
+            Color::new(0.0, 0.0, 1.0),
+        );
+
+        assert_eq!(canvas.width(), 3);
+        assert_eq!(canvas.height(), 2);
+        assert_eq!(*canvas.get_color_at(0, 0).unwrap(), red);
+        assert_eq!(*canvas.get_color_at(1, 0).unwrap(), green);
+        assert_eq!(*canvas.get_color_at(2, 0).unwrap(), blue);
+        assert_eq!(
+            *canvas.get_color_at(0, 1).unwrap(),
+            Color::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(*canvas.get_color_at(1, 1).unwrap(), blue);
+        assert_eq!(*canvas.get_color_at(2, 1).unwrap(), green);
+    }
+
+    #[test]
+    fn reading_grayscale_images() {
+        // 1-bit: 10 pixels spread over two bytes
+        let png = build_png(10, 1, 1, 0, &[0, 0b1010_0000, 0b0100_0000]);
+        let canvas = Canvas::read_png(png.as_slice()).unwrap();
+        let white = Color::new(1.0, 1.0, 1.0);
+        let black = Color::new(0.0, 0.0, 0.0);
+        assert_eq!(*canvas.get_color_at(0, 0).unwrap(), white);
+        assert_eq!(*canvas.get_color_at(1, 0).unwrap(), black);
+        assert_eq!(*canvas.get_color_at(2, 0).unwrap(), white);
+        assert_eq!(*canvas.get_color_at(9, 0).unwrap(), white);
+
+        // 4-bit
+        let png = build_png(2, 1, 4, 0, &[0, 0x5f]);
+        let canvas = Canvas::read_png(png.as_slice()).unwrap();
+        assert_eq!(
+            *canvas.get_color_at(0, 0).unwrap(),
+            Color::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)
+        );
+        assert_eq!(*canvas.get_color_at(1, 0).unwrap(), white);
+
+        // 16-bit with alpha, with the second row filtered by Up
+        let png = build_png(
+            1,
+            2,
+            16,
+            4,
+            &[0, 0x80, 0x00, 0x12, 0x34, 2, 0x40, 0x00, 0x00, 0x00],
+        );
+        let canvas = Canvas::read_png(png.as_slice()).unwrap();
+        let gray = 0x8000 as f64 / 65535.0;
+        assert_eq!(
+            *canvas.get_color_at(0, 0).unwrap(),
+            Color::new(gray, gray, gray)
+        );
+        let gray = 0xc000 as f64 / 65535.0;
+        assert_eq!(
+            *canvas.get_color_at(0, 1).unwrap(),
+            Color::new(gray, gray, gray)
+        );
+    }
+
+    #[test]
+    fn invalid_signature() {
+        assert_eq!(
+            Canvas::read_png(&b"P6\n1 1\n255\n"[..]).unwrap_err(),
+            PngError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn corrupted_chunk() {
+        let mut png = Canvas::new(2, 2).to_png();
+        png[20] ^= 0xff;
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::CrcMismatch {
+                chunk: "IHDR".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn truncated_image() {
+        let png = Canvas::new(2, 2).to_png();
+        assert_eq!(
+            Canvas::read_png(&png[..png.len() - 5]).unwrap_err(),
+            PngError::UnexpectedEnd
+        );
+
+        // the image data holds a single row out of two
+        let png = build_png(1, 2, 8, 0, &[0, 0]);
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::UnexpectedEnd
+        );
+    }
+
+    #[test]
+    fn unsupported_images() {
+        let mut ihdr = vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 1];
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &ihdr).unwrap();
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::Unsupported {
+                feature: "interlaced image".to_string()
+            }
+        );
+
+        ihdr[8] = 4;
+        ihdr[12] = 0;
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &ihdr).unwrap();
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::InvalidHeader {
+                reason: "bit depth is not allowed for the color type"
+            }
+        );
+    }
+
+    #[test]
+    fn oversized_header() {
+        let mut png = SIGNATURE.to_vec();
+        let ihdr = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 16, 6, 0, 0, 0,
+        ];
+        write_chunk(&mut png, b"IHDR", &ihdr).unwrap();
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::InvalidHeader {
+                reason: "image is wider or higher than 2^31 - 1 pixels"
+            }
+        );
+
+        // the largest allowed size holds more bytes than can be addressed
+        let png = build_png(i32::MAX as u32, i32::MAX as u32, 16, 6, &[0; 9]);
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            image_too_large()
+        );
+    }
+
+    #[test]
+    fn oversized_image_data() {
+        // a 1x1 image whose data inflates to ten megabytes
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]).unwrap();
+        let idat = zlib::compress(&vec![0; 10_000_000], PngCompression::FixedHuffman);
+        assert!(idat.len() < 10_000_000 / 100);
+        write_chunk(&mut png, b"IDAT", &idat).unwrap();
+        write_chunk(&mut png, b"IEND", &[]).unwrap();
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::InvalidData {
+                reason: "decompressed data is larger than expected"
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_filter_type() {
+        let png = build_png(1, 2, 8, 0, &[0, 0, 5, 0]);
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::InvalidFilter { row: 1, filter: 5 }
+        );
+    }
+
+    #[test]
+    fn missing_chunks() {
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IEND", &[]).unwrap();
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::MissingChunk { chunk: "IHDR" }
+        );
+
+        let mut png = build_png(1, 1, 8, 3, &[0, 0]);
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::MissingChunk { chunk: "PLTE" }
+        );
+
+        png.truncate(SIGNATURE.len() + 25);
+        write_chunk(&mut png, b"IEND", &[]).unwrap();
+        assert_eq!(
+            Canvas::read_png(png.as_slice()).unwrap_err(),
+            PngError::MissingChunk { chunk: "IDAT" }
+        );
+    }
+}
@@ -0,0 +1,652 @@
+//! The zlib format (RFC 1950) wrapping DEFLATE compressed data (RFC 1951).
+//! Compression writes either stored blocks or a single block of fixed
+//! Huffman codes; decompression reads every kind of block.
+
+/// Size of the sliding window, which is also the furthest back a match may
+/// refer to
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many earlier positions sharing the same hash are tried when looking
+/// for a match
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+/// Largest amount of data in a stored block
+const MAX_STORED: usize = 65535;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which the code lengths of the code length alphabet are stored
+/// in a dynamic block
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// How the data is compressed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    /// No compression, the data is copied into stored blocks
+    Stored,
+    /// LZ77 matching, encoded with the fixed Huffman codes of DEFLATE
+    FixedHuffman,
+}
+
+/// Compresses data into a zlib stream
+pub fn compress(data: &[u8], compression: Compression) -> Vec<u8> {
+    // deflate, with a 32K window, and a check value making the header a
+    // multiple of 31
+    let mut writer = BitWriter::new(vec![0x78, 0x01]);
+
+    match compression {
+        Compression::Stored => write_stored(&mut writer, data),
+        Compression::FixedHuffman => write_fixed(&mut writer, data),
+    }
+
+    let mut output = writer.finish();
+    output.extend_from_slice(&adler32(data).to_be_bytes());
+    output
+}
+
+/// Decompresses a zlib stream, checking its header and checksum. Fails as
+/// soon as the output grows past `limit` bytes.
+pub fn decompress(data: &[u8], limit: usize) -> Result<Vec<u8>, &'static str> {
+    let [cmf, flg, ..] = *data else {
+        return Err("zlib header is truncated");
+    };
+    if cmf & 0x0f != 8 || cmf >> 4 > 7 {
+        return Err("zlib stream is not deflate compressed");
+    }
+    if !(cmf as u16 * 256 + flg as u16).is_multiple_of(31) {
+        return Err("zlib header check failed");
+    }
+    if flg & 0x20 != 0 {
+        return Err("zlib preset dictionaries are not supported");
+    }
+
+    let mut reader = BitReader::new(&data[2..]);
+    let output = inflate(&mut reader, limit)?;
+
+    let checksum = reader.aligned_bytes(4).ok_or("zlib checksum is missing")?;
+    if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != adler32(&output)
+    {
+        return Err("zlib checksum does not match");
+    }
+    Ok(output)
+}
+
+/// Computes the Adler-32 checksum of zlib streams
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    // the sums cannot overflow within 5552 bytes
+    const CHUNK: usize = 5552;
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for chunk in data.chunks(CHUNK) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+fn write_stored(writer: &mut BitWriter, data: &[u8]) {
+    let mut blocks = data.chunks(MAX_STORED).peekable();
+    if blocks.peek().is_none() {
+        // an empty stream still needs a final block
+        writer.bits(1, 3);
+        writer.stored_block(&[]);
+    }
+    while let Some(block) = blocks.next() {
+        writer.bits(blocks.peek().is_none() as u32, 3);
+        writer.stored_block(block);
+    }
+}
+
+fn write_fixed(writer: &mut BitWriter, data: &[u8]) {
+    // final block, of fixed Huffman codes
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+
+    let mut matcher = Matcher::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        match matcher.find(data, pos) {
+            Some((length, distance)) => {
+                writer.fixed_length(length);
+                writer.fixed_distance(distance);
+                for skipped in pos..pos + length {
+                    matcher.insert(data, skipped);
+                }
+                pos += length;
+            }
+            None => {
+                writer.fixed_symbol(data[pos] as u16);
+                matcher.insert(data, pos);
+                pos += 1;
+            }
+        }
+    }
+    writer.fixed_symbol(256);
+}
+
+/// Finds earlier occurrences of the bytes at a position, through chains of
+/// positions starting with the same three bytes
+struct Matcher {
+    head: Vec<usize>,
+    previous: Vec<usize>,
+}
+
+impl Matcher {
+    /// Marks positions without a predecessor
+    const NONE: usize = usize::MAX;
+
+    fn new() -> Self {
+        Self {
+            head: vec![Self::NONE; 1 << HASH_BITS],
+            previous: vec![Self::NONE; WINDOW_SIZE],
+        }
+    }
+
+    fn hash(data: &[u8], pos: usize) -> usize {
+        let value = (data[pos] as u32) << 16 | (data[pos + 1] as u32) << 8 | data[pos + 2] as u32;
+        (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, data: &[u8], pos: usize) {
+        if pos + MIN_MATCH > data.len() {
+            return;
+        }
+        let hash = Self::hash(data, pos);
+        self.previous[pos % WINDOW_SIZE] = self.head[hash];
+        self.head[hash] = pos;
+    }
+
+    /// Returns the length and distance of the longest match for the data at
+    /// `pos`, if any
+    fn find(&self, data: &[u8], pos: usize) -> Option<(usize, usize)> {
+        if pos + MIN_MATCH > data.len() {
+            return None;
+        }
+        let max_length = MAX_MATCH.min(data.len() - pos);
+        let mut candidate = self.head[Self::hash(data, pos)];
+        let mut best: Option<(usize, usize)> = None;
+
+        for _ in 0..MAX_CHAIN {
+            if candidate == Self::NONE || pos - candidate > WINDOW_SIZE - 1 {
+                break;
+            }
+            let length = data[candidate..]
+                .iter()
+                .zip(&data[pos..pos + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length >= MIN_MATCH && best.is_none_or(|(best_length, _)| length > best_length) {
+                best = Some((length, pos - candidate));
+                if length == max_length {
+                    break;
+                }
+            }
+            let previous = self.previous[candidate % WINDOW_SIZE];
+            // the slot may have been reused by a more recent position
+            if previous != Self::NONE && previous >= candidate {
+                break;
+            }
+            candidate = previous;
+        }
+        best
+    }
+}
+
+/// Writes bits least significant first, as DEFLATE packs them
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new(output: Vec<u8>) -> Self {
+        Self {
+            output,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, whose bits are packed most significant first
+    fn code(&mut self, code: u32, length: u32) {
+        self.bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn align(&mut self) {
+        if self.count > 0 {
+            self.bits(0, 8 - self.count);
+        }
+    }
+
+    fn stored_block(&mut self, block: &[u8]) {
+        self.align();
+        let length = block.len() as u16;
+        self.output.extend_from_slice(&length.to_le_bytes());
+        self.output.extend_from_slice(&(!length).to_le_bytes());
+        self.output.extend_from_slice(block);
+    }
+
+    /// Writes a literal, length or end of block symbol with the fixed codes
+    fn fixed_symbol(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn fixed_length(&mut self, length: usize) {
+        let index = LENGTH_BASE
+            .iter()
+            .rposition(|&base| base as usize <= length)
+            .unwrap();
+        self.fixed_symbol(257 + index as u16);
+        self.bits(
+            (length - LENGTH_BASE[index] as usize) as u32,
+            LENGTH_EXTRA[index] as u32,
+        );
+    }
+
+    fn fixed_distance(&mut self, distance: usize) {
+        let index = DISTANCE_BASE
+            .iter()
+            .rposition(|&base| base as usize <= distance)
+            .unwrap();
+        self.code(index as u32, 5);
+        self.bits(
+            (distance - DISTANCE_BASE[index] as usize) as u32,
+            DISTANCE_EXTRA[index] as u32,
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.output
+    }
+}
+
+/// Reads bits least significant first
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            bit: 0,
+        }
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32, &'static str> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self.data.get(self.pos).ok_or("deflate data is truncated")?;
+            value |= (((byte >> self.bit) & 1) as u32) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Skips to the next byte boundary, and returns the following bytes
+    fn aligned_bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        if self.bit > 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+        let bytes = self.data.get(self.pos..self.pos + count)?;
+        self.pos += count;
+        Some(bytes)
+    }
+}
+
+/// A canonical Huffman code, decoded one bit at a time
+struct Huffman {
+    /// Number of codes of each length
+    counts: [u16; 16],
+    /// Symbols ordered by code
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, &'static str> {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // reject codes with more codes of a length than there is room for
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = left * 2 - count as i32;
+            if left < 0 {
+                return Err("invalid Huffman code lengths");
+            }
+        }
+
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, &'static str> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+
+        for length in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code")
+    }
+
+    fn fixed() -> (Self, Self) {
+        let mut lengths = [0u8; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        let literals = Self::new(&lengths).expect("Fixed codes should be valid");
+        let distances = Self::new(&[5; 30]).expect("Fixed codes should be valid");
+        (literals, distances)
+    }
+}
+
+/// Error of streams that decompress to more data than expected
+const TOO_LARGE: &str = "decompressed data is larger than expected";
+
+/// Decompresses raw DEFLATE data, up to `limit` bytes
+fn inflate(reader: &mut BitReader, limit: usize) -> Result<Vec<u8>, &'static str> {
+    let mut output = Vec::new();
+
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                let header = reader.aligned_bytes(4).ok_or("stored block is truncated")?;
+                let length = u16::from_le_bytes([header[0], header[1]]);
+                if length != !u16::from_le_bytes([header[2], header[3]]) {
+                    return Err("stored block length is corrupted");
+                }
+                let block = reader
+                    .aligned_bytes(length as usize)
+                    .ok_or("stored block is truncated")?;
+                if block.len() > limit - output.len() {
+                    return Err(TOO_LARGE);
+                }
+                output.extend_from_slice(block);
+            }
+            1 => {
+                let (literals, distances) = Huffman::fixed();
+                inflate_block(reader, &mut output, limit, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = read_dynamic_codes(reader)?;
+                inflate_block(reader, &mut output, limit, &literals, &distances)?;
+            }
+            _ => return Err("invalid deflate block type"),
+        }
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+fn read_dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), &'static str> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err("too many codes in dynamic block");
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let symbol = code_lengths.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or("repeated code length without a previous one")?;
+                (previous, 3 + reader.bits(2)?)
+            }
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err("code lengths overflow the dynamic block");
+    }
+    if lengths[256] == 0 {
+        return Err("dynamic block has no end of block code");
+    }
+
+    let literals = Huffman::new(&lengths[..literal_count])?;
+    let distances = Huffman::new(&lengths[literal_count..])?;
+    Ok((literals, distances))
+}
+
+fn inflate_block(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), &'static str> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            0..=255 if output.len() == limit => return Err(TOO_LARGE),
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length =
+                    LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+
+                let index = distances.decode(reader)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err("invalid distance code");
+                }
+                let distance = DISTANCE_BASE[index] as usize
+                    + reader.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                if distance > output.len() {
+                    return Err("distance refers before the start of the data");
+                }
+                if length > limit - output.len() {
+                    return Err(TOO_LARGE);
+                }
+
+                // copied one byte at a time, as a match may overlap itself
+                let start = output.len() - distance;
+                for i in 0..length {
+                    output.push(output[start + i]);
+                }
+            }
+            _ => return Err("invalid length code"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_data() -> Vec<u8> {
+        let mut data = b"The ray tracer challenge. ".repeat(40);
+        data.extend((0..5000u32).map(|i| (i * i % 251) as u8));
+        data.extend(std::iter::repeat_n(7, 1000));
+        data
+    }
+
+    #[test]
+    fn adler32_checksum() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        // long enough for the sums to wrap around
+        assert_eq!(adler32(&vec![0xff; 100_000]), 0x149a_302c);
+    }
+
+    #[test]
+    fn round_trip_through_stored_blocks() {
+        for data in [vec![], b"abc".to_vec(), vec![42; 70_000], sample_data()] {
+            let compressed = compress(&data, Compression::Stored);
+            assert_eq!(
+                compressed.len(),
+                data.len() + 2 + 4 + 5 * data.len().div_ceil(MAX_STORED).max(1)
+            );
+            assert_eq!(decompress(&compressed, usize::MAX).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn round_trip_through_fixed_huffman() {
+        for data in [vec![], b"abc".to_vec(), vec![42; 70_000], sample_data()] {
+            let compressed = compress(&data, Compression::FixedHuffman);
+            assert_eq!(decompress(&compressed, usize::MAX).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn repeated_data_is_compressed() {
+        let data = sample_data();
+        let compressed = compress(&data, Compression::FixedHuffman);
+        assert!(
+            compressed.len() < data.len() / 2,
+            "{} bytes",
+            compressed.len()
+        );
+    }
+
+    #[test]
+    fn decompressing_fixed_huffman_block() {
+        // as compressed by zlib
+        let compressed = [
+            0x78, 0x9c, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x90, 0x00, 0x3a, 0x2e,
+            0x06, 0x7d,
+        ];
+        assert_eq!(
+            decompress(&compressed, usize::MAX).unwrap(),
+            b"hello hello hello"
+        );
+    }
+
+    #[test]
+    fn decompressing_dynamic_huffman_block() {
+        // random letters, with the frequencies of English text: zlib picks
+        // codes fitted to them
+        let mut seed = 1u32;
+        let data: Vec<u8> = (0..60)
+            .map(|_| {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345) & 0x7fff_ffff;
+                b"eeeeetttaaoinshr"[(seed >> 16) as usize % 16]
+            })
+            .collect();
+        let compressed = [
+            0x78, 0xda, 0x15, 0x89, 0x41, 0x0a, 0x00, 0x41, 0x0c, 0xc2, 0xde, 0xea, 0x41, 0xd0,
+            0x4b, 0x0b, 0x8e, 0xff, 0x67, 0xbb, 0x39, 0x84, 0x40, 0x2a, 0xda, 0xa6, 0xd9, 0x97,
+            0x19, 0x96, 0xa1, 0x4e, 0x3d, 0xb0, 0x18, 0x6c, 0xff, 0x47, 0xdc, 0x4a, 0x55, 0x2f,
+            0x71, 0xbd, 0x55, 0x80, 0x0f, 0x02, 0x56, 0x19, 0x27,
+        ];
+        assert_eq!((compressed[2] >> 1) & 3, 2);
+        assert_eq!(decompress(&compressed, usize::MAX).unwrap(), data);
+    }
+
+    #[test]
+    fn corrupted_streams_are_rejected() {
+        let mut compressed = compress(b"some data to corrupt", Compression::FixedHuffman);
+        assert_eq!(
+            decompress(&compressed[..1], usize::MAX),
+            Err("zlib header is truncated")
+        );
+        assert_eq!(
+            decompress(&[0x78, 0x02], usize::MAX),
+            Err("zlib header check failed")
+        );
+
+        let last = compressed.len() - 1;
+        compressed[last] ^= 1;
+        assert_eq!(
+            decompress(&compressed, usize::MAX),
+            Err("zlib checksum does not match")
+        );
+        assert_eq!(
+            decompress(&compressed[..compressed.len() - 6], usize::MAX),
+            Err("deflate data is truncated")
+        );
+    }
+
+    #[test]
+    fn output_is_limited() {
+        let data = sample_data();
+        for compression in [Compression::Stored, Compression::FixedHuffman] {
+            let compressed = compress(&data, compression);
+            assert_eq!(decompress(&compressed, data.len()).unwrap(), data);
+            assert_eq!(decompress(&compressed, data.len() - 1), Err(TOO_LARGE));
+        }
+    }
+}
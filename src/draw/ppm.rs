@@ -0,0 +1,859 @@
+use crate::draw::{Canvas, Color, ToneMap};
+use std::{
+    error, fmt,
+    io::{self, Write},
+};
+
+/// Longest line allowed in the plain (P3) format
+const MAX_LINE_LEN: usize = 70;
+
+/// The encodings of a portable pixmap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PpmFormat {
+    /// P3: samples written as decimal text
+    Plain,
+    /// P6: samples written as bytes, or as big endian pairs of bytes when
+    /// the maximum value is above 255
+    Binary,
+}
+
+/// Error raised while reading a PPM, PGM or PFM image. Errors carry the
+/// position they occurred at, as a (1-indexed) line and a (0-indexed) byte
+/// offset from the start of the data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PpmError {
+    /// The data does not start with one of the supported magic numbers
+    UnknownFormat { magic: String },
+    /// A header value or a plain sample is not a valid number
+    InvalidNumber {
+        line: usize,
+        byte: usize,
+        value: String,
+    },
+    /// The maximum value is not between 1 and 65535
+    InvalidMaxval {
+        line: usize,
+        byte: usize,
+        maxval: u64,
+    },
+    /// The scale of a PFM image is zero, which leaves its byte order unknown
+    InvalidScale { line: usize, byte: usize },
+    /// A sample is greater than the maximum value of the image
+    SampleOutOfRange {
+        line: usize,
+        byte: usize,
+        value: u64,
+        maxval: u16,
+    },
+    /// The data ends before every pixel of the image was read
+    UnexpectedEnd { line: usize, byte: usize },
+    /// The single whitespace byte ending the header of a binary image is
+    /// something else
+    MissingWhitespace { line: usize, byte: usize },
+    /// The number of bytes of the image overflows the address space
+    InvalidSize { line: usize, byte: usize },
+}
+
+impl fmt::Display for PpmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PpmError::UnknownFormat { magic } => {
+                write!(f, "unknown image format {magic:?}")
+            }
+            PpmError::InvalidNumber { line, byte, value } => {
+                write!(f, "line {line}, byte {byte}: invalid number {value:?}")
+            }
+            PpmError::InvalidMaxval { line, byte, maxval } => write!(
+                f,
+                "line {line}, byte {byte}: maximum value {maxval} is not between 1 and 65535"
+            ),
+            PpmError::InvalidScale { line, byte } => {
+                write!(f, "line {line}, byte {byte}: PFM scale should not be zero")
+            }
+            PpmError::SampleOutOfRange {
+                line,
+                byte,
+                value,
+                maxval,
+            } => write!(
+                f,
+                "line {line}, byte {byte}: sample {value} is greater than {maxval}"
+            ),
+            PpmError::UnexpectedEnd { line, byte } => {
+                write!(f, "line {line}, byte {byte}: unexpected end of data")
+            }
+            PpmError::MissingWhitespace { line, byte } => {
+                write!(f, "line {line}, byte {byte}: expected whitespace")
+            }
+            PpmError::InvalidSize { line, byte } => {
+                write!(f, "line {line}, byte {byte}: image is too large")
+            }
+        }
+    }
+}
+
+impl error::Error for PpmError {}
+
+impl Canvas {
+    /// Reads an image in one of the Netpbm formats:
+    /// - P3 and P6, plain and binary color pixmaps
+    /// - P2 and P5, plain and binary grayscale graymaps
+    /// - PF and Pf, color and grayscale floating point maps (PFM)
+    ///
+    /// Samples are scaled from `0..=maxval` to 0..1, while PFM samples are
+    /// read as they are (the magnitude of their scale is ignored, only its
+    /// sign is used, for the byte order). Grayscale images give gray colors.
+    pub fn from_ppm(data: &[u8]) -> Result<Canvas, PpmError> {
+        let mut reader = Reader::new(data);
+        let mut magic = data.get(..2).unwrap_or(data);
+        // the magic number is a word of its own, "P31" is not "P3"
+        if data.get(2).is_some_and(|byte| !byte.is_ascii_whitespace()) {
+            magic = &data[..3];
+        }
+        let (channels, encoding) = match magic {
+            b"P3" => (3, Encoding::Plain),
+            b"P6" => (3, Encoding::Binary),
+            b"P2" => (1, Encoding::Plain),
+            b"P5" => (1, Encoding::Binary),
+            b"PF" => (3, Encoding::Float),
+            b"Pf" => (1, Encoding::Float),
+            _ => {
+                return Err(PpmError::UnknownFormat {
+                    magic: String::from_utf8_lossy(magic).into_owned(),
+                });
+            }
+        };
+        reader.pos = 2;
+
+        reader.skip_blanks();
+        let (line, byte) = (reader.line, reader.pos);
+        let width = reader.number::<usize>()?;
+        let height = reader.number::<usize>()?;
+        let samples = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(channels))
+            .ok_or(PpmError::InvalidSize { line, byte })?;
+
+        let values = match encoding {
+            Encoding::Plain => {
+                let maxval = reader.maxval()?;
+                // every sample takes at least one digit and one separator,
+                // which is checked before allocating the canvas
+                if reader.remaining() < samples {
+                    return Err(reader.unexpected_end());
+                }
+                reader.plain_samples(samples, maxval)?
+            }
+            Encoding::Binary => {
+                let maxval = reader.maxval()?;
+                reader.single_whitespace()?;
+                reader.binary_samples(samples, maxval)?
+            }
+            Encoding::Float => {
+                let little_endian = reader.scale()?;
+                reader.single_whitespace()?;
+                reader.float_samples(samples, little_endian)?
+            }
+        };
+
+        let mut canvas = Canvas::new(width, height);
+        for (i, pixel) in values.chunks_exact(channels).enumerate() {
+            let color = match pixel {
+                [red, green, blue] => Color::new(*red, *green, *blue),
+                _ => Color::new(pixel[0], pixel[0], pixel[0]),
+            };
+            // PFM images are stored from the bottom row to the top
+            let (x, y) = match encoding {
+                Encoding::Float => (i % width, height - 1 - i / width),
+                _ => (i % width, i / width),
+            };
+            canvas
+                .set_pixel_at(x, y, &color)
+                .expect("Pixel should be within the canvas");
+        }
+        Ok(canvas)
+    }
+
+    /// Streams the canvas to `writer` as a PPM image, one row at a time.
+    /// Color components are clamped to 0..1 and scaled to `0..=maxval`;
+    /// a maxval of 65535 gives 16-bit samples.
+    ///
+    /// Writing to a file through a `BufWriter` is recommended for the plain
+    /// format, which is written in many small pieces.
+    /// ## Errors
+    /// Returns an error of kind `InvalidInput` if maxval is zero, and any
+    /// error of the writer.
+    pub fn write_ppm<W: Write>(&self, writer: W, format: PpmFormat, maxval: u16) -> io::Result<()> {
+        self.write_ppm_with(writer, format, maxval, &ToneMap::default())
+    }
+
+    /// Streams the canvas to `writer` as a PPM image, like `write_ppm`, with
+    /// colors mapped to 0..1 by the given tone map instead of clamped.
+    /// ## Errors
+    /// Returns an error of kind `InvalidInput` if maxval is zero, and any
+    /// error of the writer.
+    pub fn write_ppm_with<W: Write>(
+        &self,
+        mut writer: W,
+        format: PpmFormat,
+        maxval: u16,
+        tone_map: &ToneMap,
+    ) -> io::Result<()> {
+        if maxval == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "PPM maximum value should be between 1 and 65535",
+            ));
+        }
+        let magic = match format {
+            PpmFormat::Plain => "P3",
+            PpmFormat::Binary => "P6",
+        };
+        write!(
+            writer,
+            "{magic}\n{} {}\n{maxval}\n",
+            self.width(),
+            self.height()
+        )?;
+
+        let scale = tone_map.exposure_scale(self);
+        let mut row = Vec::new();
+        for y in 0..self.height() {
+            row.clear();
+            let colors = (0..self.width()).map(|x| {
+                let color = self
+                    .get_color_at(x, y)
+                    .expect("Pixel should be within the canvas");
+                tone_map.map_color(color, scale)
+            });
+            match format {
+                PpmFormat::Plain => plain_row(&mut row, colors, maxval),
+                PpmFormat::Binary => binary_row(&mut row, colors, maxval),
+            }
+            writer.write_all(&row)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the content of canvas to a binary PPM (P6) with 8-bit samples
+    pub fn to_ppm_binary(&self) -> Vec<u8> {
+        self.to_ppm_binary_with(&ToneMap::default())
+    }
+
+    /// Writes the content of canvas to a binary PPM (P6) with 8-bit samples,
+    /// with colors mapped to 0..1 by the given tone map
+    pub fn to_ppm_binary_with(&self, tone_map: &ToneMap) -> Vec<u8> {
+        let mut ppm = Vec::with_capacity(self.size() * 3 + 20);
+        self.write_ppm_with(&mut ppm, PpmFormat::Binary, 255, tone_map)
+            .expect("Writing to a vector should not fail");
+        ppm
+    }
+}
+
+/// Scales a color component from 0..1 to 0..=maxval
+pub(crate) fn quantize(value: f64, maxval: u16) -> u16 {
+    (value.clamp(0.0, 1.0) * maxval as f64).round() as u16
+}
+
+/// Appends a row of samples as text, wrapping lines before they get longer
+/// than `MAX_LINE_LEN`, and ending with a new line
+fn plain_row(row: &mut Vec<u8>, colors: impl Iterator<Item = Color>, maxval: u16) {
+    let mut line_len = 0;
+
+    for color in colors {
+        for value in [color.red, color.green, color.blue] {
+            let sample = quantize(value, maxval).to_string();
+            if line_len > 0 && line_len + 1 + sample.len() > MAX_LINE_LEN {
+                row.push(b'\n');
+                line_len = 0;
+            } else if line_len > 0 {
+                row.push(b' ');
+                line_len += 1;
+            }
+            row.extend_from_slice(sample.as_bytes());
+            line_len += sample.len();
+        }
+    }
+    row.push(b'\n');
+}
+
+fn binary_row(row: &mut Vec<u8>, colors: impl Iterator<Item = Color>, maxval: u16) {
+    for color in colors {
+        for value in [color.red, color.green, color.blue] {
+            let sample = quantize(value, maxval);
+            if maxval > 255 {
+                row.extend_from_slice(&sample.to_be_bytes());
+            } else {
+                row.push(sample as u8);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Plain,
+    Binary,
+    Float,
+}
+
+/// A cursor over the data of an image, keeping track of the current line
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn unexpected_end(&self) -> PpmError {
+        PpmError::UnexpectedEnd {
+            line: self.line,
+            byte: self.data.len(),
+        }
+    }
+
+    /// Returns the error of samples whose size in bytes overflows, at the
+    /// start of the samples
+    fn invalid_size(&self) -> PpmError {
+        PpmError::InvalidSize {
+            line: self.line,
+            byte: self.pos,
+        }
+    }
+
+    /// Skips whitespace, and comments running from a '#' to the end of the
+    /// line
+    fn skip_blanks(&mut self) {
+        while let Some(&byte) = self.data.get(self.pos) {
+            match byte {
+                b'#' => {
+                    while self.data.get(self.pos).is_some_and(|&b| b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                b'\n' => {
+                    self.line += 1;
+                    self.pos += 1;
+                }
+                _ if byte.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Returns the next whitespace separated word, with its line and byte
+    /// offset
+    fn word(&mut self) -> Result<(&'a [u8], usize, usize), PpmError> {
+        self.skip_blanks();
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b'#')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.unexpected_end());
+        }
+        Ok((&self.data[start..self.pos], self.line, start))
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, PpmError> {
+        let (word, line, byte) = self.word()?;
+        std::str::from_utf8(word)
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .ok_or_else(|| PpmError::InvalidNumber {
+                line,
+                byte,
+                value: String::from_utf8_lossy(word).into_owned(),
+            })
+    }
+
+    fn maxval(&mut self) -> Result<u16, PpmError> {
+        self.skip_blanks();
+        let (line, byte) = (self.line, self.pos);
+        let maxval = self.number::<u64>()?;
+
+        match u16::try_from(maxval) {
+            Ok(maxval) if maxval > 0 => Ok(maxval),
+            _ => Err(PpmError::InvalidMaxval { line, byte, maxval }),
+        }
+    }
+
+    /// Reads the scale of a PFM image, returning true if its samples are
+    /// little endian
+    fn scale(&mut self) -> Result<bool, PpmError> {
+        self.skip_blanks();
+        let (line, byte) = (self.line, self.pos);
+        let scale = self.number::<f64>()?;
+
+        if scale == 0.0 || scale.is_nan() {
+            return Err(PpmError::InvalidScale { line, byte });
+        }
+        Ok(scale < 0.0)
+    }
+
+    /// Skips the single whitespace byte separating the header of a binary
+    /// image from its samples
+    fn single_whitespace(&mut self) -> Result<(), PpmError> {
+        match self.data.get(self.pos) {
+            Some(byte) if byte.is_ascii_whitespace() => {
+                if *byte == b'\n' {
+                    self.line += 1;
+                }
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(PpmError::MissingWhitespace {
+                line: self.line,
+                byte: self.pos,
+            }),
+            None => Err(self.unexpected_end()),
+        }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], PpmError> {
+        if self.remaining() < count {
+            return Err(self.unexpected_end());
+        }
+        let bytes = &self.data[self.pos..self.pos + count];
+        self.pos += count;
+        Ok(bytes)
+    }
+
+    fn plain_samples(&mut self, count: usize, maxval: u16) -> Result<Vec<f64>, PpmError> {
+        let mut samples = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            self.skip_blanks();
+            let (line, byte) = (self.line, self.pos);
+            let value = self.number::<u64>()?;
+            if value > maxval as u64 {
+                return Err(PpmError::SampleOutOfRange {
+                    line,
+                    byte,
+                    value,
+                    maxval,
+                });
+            }
+            samples.push(value as f64 / maxval as f64);
+        }
+        Ok(samples)
+    }
+
+    fn binary_samples(&mut self, count: usize, maxval: u16) -> Result<Vec<f64>, PpmError> {
+        let size = if maxval > 255 { 2 } else { 1 };
+        let start = self.pos;
+        let bytes = self.bytes(count.checked_mul(size).ok_or_else(|| self.invalid_size())?)?;
+
+        bytes
+            .chunks_exact(size)
+            .enumerate()
+            .map(|(i, sample)| {
+                let value = match sample {
+                    [high, low] => u16::from_be_bytes([*high, *low]),
+                    _ => sample[0] as u16,
+                };
+                if value > maxval {
+                    return Err(PpmError::SampleOutOfRange {
+                        line: self.line,
+                        byte: start + i * size,
+                        value: value as u64,
+                        maxval,
+                    });
+                }
+                Ok(value as f64 / maxval as f64)
+            })
+            .collect()
+    }
+
+    fn float_samples(&mut self, count: usize, little_endian: bool) -> Result<Vec<f64>, PpmError> {
+        let bytes = self.bytes(count.checked_mul(4).ok_or_else(|| self.invalid_size())?)?;
+
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|sample| {
+                let sample = [sample[0], sample[1], sample[2], sample[3]];
+                let value = if little_endian {
+                    f32::from_le_bytes(sample)
+                } else {
+                    f32::from_be_bytes(sample)
+                };
+                value as f64
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::test_util::{assert_same_image, gradient};
+
+    #[test]
+    fn plain_output_matches_to_ppm() {
+        for (width, height) in [(5, 3), (10, 2), (33, 4), (0, 0)] {
+            let canvas = gradient(width, height, 1.5);
+            let mut ppm = vec![];
+            canvas.write_ppm(&mut ppm, PpmFormat::Plain, 255).unwrap();
+
+            assert_eq!(String::from_utf8(ppm).unwrap(), canvas.to_ppm());
+        }
+    }
+
+    #[test]
+    fn plain_output_with_16_bit_samples() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas
+            .set_pixel_at(0, 0, &Color::new(1.0, 0.5, 0.0))
+            .unwrap();
+        canvas
+            .set_pixel_at(1, 0, &Color::new(-1.0, 2.0, 0.25))
+            .unwrap();
+        let mut ppm = vec![];
+        canvas.write_ppm(&mut ppm, PpmFormat::Plain, 65535).unwrap();
+
+        assert_eq!(
+            String::from_utf8(ppm).unwrap(),
+            "P3\n2 1\n65535\n65535 32768 0 0 65535 16384\n"
+        );
+    }
+
+    #[test]
+    fn plain_lines_are_wrapped() {
+        let canvas = gradient(40, 2, 1.5);
+        let mut ppm = vec![];
+        canvas.write_ppm(&mut ppm, PpmFormat::Plain, 65535).unwrap();
+        let ppm = String::from_utf8(ppm).unwrap();
+
+        assert!(ppm.lines().all(|line| line.len() <= MAX_LINE_LEN));
+        let samples: Vec<&str> = ppm.split_whitespace().skip(4).collect();
+        assert_eq!(samples.len(), 40 * 2 * 3);
+    }
+
+    #[test]
+    fn binary_output() {
+        let mut canvas = Canvas::new(2, 2);
+        canvas
+            .set_pixel_at(0, 0, &Color::new(1.0, 0.5, 0.0))
+            .unwrap();
+        canvas
+            .set_pixel_at(1, 1, &Color::new(0.2, 1.2, -0.5))
+            .unwrap();
+        let ppm = canvas.to_ppm_binary();
+
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(
+            &ppm[header.len()..],
+            &[255, 128, 0, 0, 0, 0, 0, 0, 0, 51, 255, 0]
+        );
+    }
+
+    #[test]
+    fn binary_output_with_16_bit_samples() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas
+            .set_pixel_at(0, 0, &Color::new(1.0, 0.5, 0.0))
+            .unwrap();
+        let mut ppm = vec![];
+        canvas
+            .write_ppm(&mut ppm, PpmFormat::Binary, 65535)
+            .unwrap();
+
+        let header = b"P6\n1 1\n65535\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(&ppm[header.len()..], &[0xff, 0xff, 0x80, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn binary_output_with_custom_maxval() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas
+            .set_pixel_at(0, 0, &Color::new(1.0, 0.5, 0.0))
+            .unwrap();
+        let mut ppm = vec![];
+        canvas.write_ppm(&mut ppm, PpmFormat::Binary, 15).unwrap();
+
+        assert_eq!(ppm, b"P6\n1 1\n15\n\x0f\x08\x00");
+    }
+
+    #[test]
+    fn zero_maxval_is_rejected() {
+        let canvas = Canvas::new(1, 1);
+        let error = canvas.write_ppm(vec![], PpmFormat::Binary, 0).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn writer_errors_are_returned() {
+        struct Failing;
+        impl Write for Failing {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let error = Canvas::new(1, 1)
+            .write_ppm(Failing, PpmFormat::Plain, 255)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "disk full");
+    }
+
+    #[test]
+    fn reading_plain_ppm() {
+        let ppm = b"P3
+# a comment, and a pixel per line
+2 1 # another one
+255
+255 0 0
+  0\t128\r\n   255
+";
+        let canvas = Canvas::from_ppm(ppm).unwrap();
+
+        assert_eq!(canvas.width(), 2);
+        assert_eq!(canvas.height(), 1);
+        assert_eq!(
+            *canvas.get_color_at(0, 0).unwrap(),
+            Color::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            *canvas.get_color_at(1, 0).unwrap(),
+            Color::new(0.0, 128.0 / 255.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn round_trip_through_to_ppm() {
+        let canvas = gradient(30, 7, 0.5);
+        let read = Canvas::from_ppm(canvas.to_ppm().as_bytes()).unwrap();
+        assert_same_image(&canvas, &read, 255.0);
+    }
+
+    #[test]
+    fn round_trip_through_binary_ppm() {
+        let canvas = gradient(30, 7, 0.5);
+        let read = Canvas::from_ppm(&canvas.to_ppm_binary()).unwrap();
+        assert_same_image(&canvas, &read, 255.0);
+
+        for format in [PpmFormat::Plain, PpmFormat::Binary] {
+            let mut ppm = vec![];
+            canvas.write_ppm(&mut ppm, format, 65535).unwrap();
+            let read = Canvas::from_ppm(&ppm).unwrap();
+            assert_same_image(&canvas, &read, 65535.0);
+        }
+    }
+
+    #[test]
+    fn samples_are_scaled_by_maxval() {
+        let canvas = Canvas::from_ppm(b"P6 1 1 15\n\x0f\x05\x00").unwrap();
+        assert_eq!(
+            *canvas.get_color_at(0, 0).unwrap(),
+            Color::new(1.0, 1.0 / 3.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn reading_graymaps() {
+        let plain = Canvas::from_ppm(b"P2\n2 1\n10\n5 10\n").unwrap();
+        let binary = Canvas::from_ppm(b"P5\n2 1\n65535\n\x80\x00\xff\xff").unwrap();
+
+        assert_eq!(
+            *plain.get_color_at(0, 0).unwrap(),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            *plain.get_color_at(1, 0).unwrap(),
+            Color::new(1.0, 1.0, 1.0)
+        );
+        let half = 32768.0 / 65535.0;
+        assert_eq!(
+            *binary.get_color_at(0, 0).unwrap(),
+            Color::new(half, half, half)
+        );
+        assert_eq!(
+            *binary.get_color_at(1, 0).unwrap(),
+            Color::new(1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn reading_float_maps() {
+        // rows are stored from the bottom up
+        let mut pfm = b"PF\n1 2\n-1.0\n".to_vec();
+        for value in [0.25f32, 0.5, 4.0, -1.0, 0.0, 1.5] {
+            pfm.extend_from_slice(&value.to_le_bytes());
+        }
+        let canvas = Canvas::from_ppm(&pfm).unwrap();
+        assert_eq!(
+            *canvas.get_color_at(0, 1).unwrap(),
+            Color::new(0.25, 0.5, 4.0)
+        );
+        assert_eq!(
+            *canvas.get_color_at(0, 0).unwrap(),
+            Color::new(-1.0, 0.0, 1.5)
+        );
+
+        let mut pfm = b"Pf\n2 1\n1.0\n".to_vec();
+        for value in [2.5f32, 0.125] {
+            pfm.extend_from_slice(&value.to_be_bytes());
+        }
+        let canvas = Canvas::from_ppm(&pfm).unwrap();
+        assert_eq!(
+            *canvas.get_color_at(0, 0).unwrap(),
+            Color::new(2.5, 2.5, 2.5)
+        );
+        assert_eq!(
+            *canvas.get_color_at(1, 0).unwrap(),
+            Color::new(0.125, 0.125, 0.125)
+        );
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert_eq!(
+            Canvas::from_ppm(b"P1\n1 1\n0").unwrap_err(),
+            PpmError::UnknownFormat {
+                magic: "P1".to_string()
+            }
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"").unwrap_err(),
+            PpmError::UnknownFormat {
+                magic: String::new()
+            }
+        );
+        // the magic number should be followed by whitespace
+        assert_eq!(
+            Canvas::from_ppm(b"P31 1 255 0 0 0").unwrap_err(),
+            PpmError::UnknownFormat {
+                magic: "P31".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn binary_header_ends_with_whitespace() {
+        let error = Canvas::from_ppm(b"P6\n1 1\n255#\n\x01\x02\x03").unwrap_err();
+        assert_eq!(error, PpmError::MissingWhitespace { line: 3, byte: 10 });
+        assert_eq!(error.to_string(), "line 3, byte 10: expected whitespace");
+    }
+
+    #[test]
+    fn invalid_numbers_report_their_position() {
+        let error = Canvas::from_ppm(b"P3\n# size\n2 x1\n255\n").unwrap_err();
+        assert_eq!(
+            error,
+            PpmError::InvalidNumber {
+                line: 3,
+                byte: 12,
+                value: "x1".to_string()
+            }
+        );
+        assert_eq!(error.to_string(), "line 3, byte 12: invalid number \"x1\"");
+    }
+
+    #[test]
+    fn invalid_maxval() {
+        assert_eq!(
+            Canvas::from_ppm(b"P3 1 1 0 0 0 0").unwrap_err(),
+            PpmError::InvalidMaxval {
+                line: 1,
+                byte: 7,
+                maxval: 0
+            }
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P6\n1 1\n65536\n").unwrap_err(),
+            PpmError::InvalidMaxval {
+                line: 3,
+                byte: 7,
+                maxval: 65536
+            }
+        );
+    }
+
+    #[test]
+    fn samples_above_maxval() {
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n1 1\n255\n0 256 0\n").unwrap_err(),
+            PpmError::SampleOutOfRange {
+                line: 4,
+                byte: 13,
+                value: 256,
+                maxval: 255
+            }
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P5 2 1 100\n\x10\x65").unwrap_err(),
+            PpmError::SampleOutOfRange {
+                line: 2,
+                byte: 12,
+                value: 101,
+                maxval: 100
+            }
+        );
+    }
+
+    #[test]
+    fn truncated_data() {
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n2 1\n255\n1 2 3\n4 5").unwrap_err(),
+            PpmError::UnexpectedEnd { line: 5, byte: 20 }
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P6\n2 1\n255\n\x01\x02\x03").unwrap_err(),
+            PpmError::UnexpectedEnd { line: 4, byte: 14 }
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P6\n100000 100000\n255\n").unwrap_err(),
+            PpmError::UnexpectedEnd { line: 4, byte: 21 }
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n2").unwrap_err(),
+            PpmError::UnexpectedEnd { line: 2, byte: 4 }
+        );
+    }
+
+    #[test]
+    fn oversized_header() {
+        assert_eq!(
+            Canvas::from_ppm(b"P6 99999999999 99999999999 255\n").unwrap_err(),
+            PpmError::InvalidSize { line: 1, byte: 3 }
+        );
+        // the number of samples fits, but not their size with two bytes each
+        assert_eq!(
+            Canvas::from_ppm(b"P6\n4000000000 1000000000\n65535\n").unwrap_err(),
+            PpmError::InvalidSize { line: 4, byte: 31 }
+        );
+        assert_eq!(
+            PpmError::InvalidSize { line: 1, byte: 3 }.to_string(),
+            "line 1, byte 3: image is too large"
+        );
+    }
+
+    #[test]
+    fn zero_pfm_scale() {
+        assert_eq!(
+            Canvas::from_ppm(b"PF\n1 1\n0.0\n").unwrap_err(),
+            PpmError::InvalidScale { line: 3, byte: 7 }
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Fixtures shared by the tests of the image formats
+
+use crate::draw::{Canvas, Color};
+
+/// A canvas whose red and green components grow from left to right and from
+/// top to bottom, with a constant blue component
+pub(crate) fn gradient(width: usize, height: usize, blue: f64) -> Canvas {
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let color = Color::new(x as f64 / width as f64, y as f64 / height as f64, blue);
+            canvas.set_pixel_at(x, y, &color).unwrap();
+        }
+    }
+    canvas
+}
+
+/// Asserts that two images have the same size and that their colors are
+/// within the rounding error of samples quantized to `0..=maxval`
+pub(crate) fn assert_same_image(a: &Canvas, b: &Canvas, maxval: f64) {
+    let tolerance = 0.5 / maxval + 1e-9;
+    assert_eq!((a.width(), a.height()), (b.width(), b.height()));
+    for y in 0..a.height() {
+        for x in 0..a.width() {
+            let (ca, cb) = (a.get_color_at(x, y).unwrap(), b.get_color_at(x, y).unwrap());
+            assert!(
+                (ca.red - cb.red).abs() <= tolerance
+                    && (ca.green - cb.green).abs() <= tolerance
+                    && (ca.blue - cb.blue).abs() <= tolerance,
+                "at ({x}, {y}): {ca:?} != {cb:?}"
+            );
+        }
+    }
+}
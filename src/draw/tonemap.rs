@@ -0,0 +1,392 @@
+use crate::{
+    EPSILON,
+    draw::{Canvas, Color},
+};
+
+/// Offset added to luminances before taking their logarithm, so that black
+/// pixels do not pull the log-average down to zero
+const LOG_DELTA: f64 = 1e-4;
+
+/// Parameters of the Hable (Uncharted 2) filmic curve: shoulder strength,
+/// linear strength, linear angle, toe strength, toe numerator and toe
+/// denominator
+const HABLE: [f64; 6] = [0.15, 0.50, 0.10, 0.20, 0.02, 0.30];
+
+/// Exposure bias applied before the Hable curve, which maps middle gray
+/// close to the middle of the output range
+const HABLE_BIAS: f64 = 2.0;
+
+/// The curve mapping colors of any brightness to displayable colors in 0..1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMapOperator {
+    /// Components are clamped to 0..1, so anything brighter than white is
+    /// clipped to white
+    Linear,
+    /// Luminance L is mapped to L / (1 + L), compressing highlights while
+    /// keeping hues
+    Reinhard,
+    /// Reinhard with a white point: luminance L is mapped to
+    /// L (1 + L / white²) / (1 + L), which reaches 1 at the white point
+    ReinhardExtended { white: f64 },
+    /// Narkowicz's fit of the ACES filmic curve, applied to each component
+    Aces,
+    /// John Hable's filmic curve from Uncharted 2, applied to each component
+    /// and scaled so that the white point maps to 1
+    Hable { white: f64 },
+}
+
+impl ToneMapOperator {
+    /// Maps a color, already scaled by the exposure, to 0..1. Negative and
+    /// NaN components are mapped to zero.
+    pub fn map(&self, color: &Color) -> Color {
+        let color = Color::new(
+            color.red.max(0.0),
+            color.green.max(0.0),
+            color.blue.max(0.0),
+        );
+        let mapped = match *self {
+            ToneMapOperator::Linear => color,
+            ToneMapOperator::Reinhard => scale_luminance(color, |l| l / (1.0 + l)),
+            ToneMapOperator::ReinhardExtended { white } => {
+                scale_luminance(color, |l| l * (1.0 + l / (white * white)) / (1.0 + l))
+            }
+            ToneMapOperator::Aces => map_components(color, |x| {
+                (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)
+            }),
+            ToneMapOperator::Hable { white } => {
+                map_components(color, |x| hable(x * HABLE_BIAS) / hable(white))
+            }
+        };
+        map_components(mapped, |x| x.clamp(0.0, 1.0))
+    }
+}
+
+/// How much light reaches the tone map operator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exposure {
+    /// Colors are multiplied by 2 to the power of this number of stops
+    Stops(f64),
+    /// Colors are scaled so that the log-average luminance of the canvas
+    /// becomes the key, 0.18 being middle gray
+    Auto { key: f64 },
+}
+
+/// The stage turning the unbounded colors of a render into displayable
+/// colors, applied by the PPM and PNG writers before quantizing: colors are
+/// scaled by the exposure, then mapped by the operator. The default tone map
+/// only clamps colors to 0..1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToneMap {
+    pub operator: ToneMapOperator,
+    pub exposure: Exposure,
+}
+
+impl ToneMap {
+    /// Creates a tone map with the given operator, and no exposure change
+    pub fn new(operator: ToneMapOperator) -> Self {
+        Self {
+            operator,
+            exposure: Exposure::Stops(0.0),
+        }
+    }
+
+    /// Returns this tone map, with an exposure change of the given number of
+    /// stops
+    pub fn with_exposure(self, stops: f64) -> Self {
+        Self {
+            exposure: Exposure::Stops(stops),
+            ..self
+        }
+    }
+
+    /// Returns this tone map, with an exposure computed from the canvas to
+    /// bring its log-average luminance to the key
+    pub fn with_auto_exposure(self, key: f64) -> Self {
+        Self {
+            exposure: Exposure::Auto { key },
+            ..self
+        }
+    }
+
+    /// Returns the factor colors of the canvas are multiplied by before
+    /// being mapped. Auto exposure leaves black (or nearly black) canvases
+    /// unchanged.
+    pub fn exposure_scale(&self, canvas: &Canvas) -> f64 {
+        match self.exposure {
+            Exposure::Stops(stops) => stops.exp2(),
+            Exposure::Auto { key } => match canvas.log_average_luminance() {
+                Some(average) if average - LOG_DELTA > EPSILON => key / average,
+                _ => 1.0,
+            },
+        }
+    }
+
+    /// Maps a color to 0..1, given the exposure scale of its canvas
+    pub(crate) fn map_color(&self, color: &Color, scale: f64) -> Color {
+        self.operator.map(&(color.clone() * scale))
+    }
+}
+
+impl Default for ToneMap {
+    fn default() -> Self {
+        Self::new(ToneMapOperator::Linear)
+    }
+}
+
+impl Canvas {
+    /// Returns a copy of the canvas with every color scaled by the exposure
+    /// and mapped to 0..1 by the operator of the tone map, ready to be
+    /// written as PPM or PNG.
+    pub fn tone_map(&self, tone_map: &ToneMap) -> Canvas {
+        let scale = tone_map.exposure_scale(self);
+        let mut canvas = Canvas::new(self.width(), self.height());
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let color = self
+                    .get_color_at(x, y)
+                    .expect("Pixel should be within the canvas");
+                canvas
+                    .set_pixel_at(x, y, &tone_map.map_color(color, scale))
+                    .expect("Pixel should be within the canvas");
+            }
+        }
+        canvas
+    }
+
+    /// Returns the geometric mean of the luminance of the pixels, which is
+    /// less sensitive than the mean to a few very bright pixels, or `None`
+    /// for an empty canvas
+    pub fn log_average_luminance(&self) -> Option<f64> {
+        if self.size() == 0 {
+            return None;
+        }
+        let mut sum = 0.0;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let color = self
+                    .get_color_at(x, y)
+                    .expect("Pixel should be within the canvas");
+                sum += (LOG_DELTA + luminance(color).max(0.0)).ln();
+            }
+        }
+        Some((sum / self.size() as f64).exp())
+    }
+}
+
+/// Returns the relative luminance of a linear color (Rec. 709 primaries)
+fn luminance(color: &Color) -> f64 {
+    0.2126 * color.red + 0.7152 * color.green + 0.0722 * color.blue
+}
+
+/// Scales a color so that its luminance becomes the mapped luminance
+fn scale_luminance(color: Color, map: impl Fn(f64) -> f64) -> Color {
+    let luminance = luminance(&color);
+    if luminance <= 0.0 {
+        return color;
+    }
+    color * (map(luminance) / luminance)
+}
+
+fn map_components(color: Color, map: impl Fn(f64) -> f64) -> Color {
+    Color::new(map(color.red), map(color.green), map(color.blue))
+}
+
+fn hable(x: f64) -> f64 {
+    let [a, b, c, d, e, f] = HABLE;
+    (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::{PngOptions, PpmFormat};
+
+    fn gray(value: f64) -> Color {
+        Color::new(value, value, value)
+    }
+
+    #[test]
+    fn linear_clamps_components() {
+        let operator = ToneMapOperator::Linear;
+
+        assert_eq!(
+            operator.map(&Color::new(0.5, 1.5, -0.5)),
+            Color::new(0.5, 1.0, 0.0)
+        );
+        assert_eq!(
+            operator.map(&Color::new(f64::NAN, 0.25, 1.0)),
+            Color::new(0.0, 0.25, 1.0)
+        );
+    }
+
+    #[test]
+    fn reinhard_compresses_luminance() {
+        let operator = ToneMapOperator::Reinhard;
+
+        assert_eq!(operator.map(&gray(1.0)), gray(0.5));
+        assert_eq!(operator.map(&gray(3.0)), gray(0.75));
+        assert_eq!(operator.map(&gray(0.0)), gray(0.0));
+        // the ratios between components are kept
+        let color = operator.map(&Color::new(0.4, 0.2, 0.1));
+        assert!((color.red / color.green - 2.0).abs() < 1e-9);
+        assert!((color.green / color.blue - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn extended_reinhard_reaches_white_at_white_point() {
+        let operator = ToneMapOperator::ReinhardExtended { white: 4.0 };
+
+        assert_eq!(operator.map(&gray(4.0)), gray(1.0));
+        assert_eq!(operator.map(&gray(8.0)), gray(1.0));
+        assert_eq!(operator.map(&gray(1.0)), gray(0.53125));
+        // a larger white point tends to the simple operator
+        let operator = ToneMapOperator::ReinhardExtended { white: 1e9 };
+        assert_eq!(operator.map(&gray(1.0)), gray(0.5));
+    }
+
+    #[test]
+    fn aces_filmic_curve() {
+        let operator = ToneMapOperator::Aces;
+
+        assert_eq!(operator.map(&gray(0.0)), gray(0.0));
+        assert_eq!(operator.map(&gray(1.0)), gray(2.54 / 3.16));
+        assert_eq!(operator.map(&gray(100.0)), gray(1.0));
+        let dark = operator.map(&gray(0.18));
+        assert!(dark.red > 0.2 && dark.red < 0.3);
+    }
+
+    #[test]
+    fn hable_filmic_curve() {
+        let operator = ToneMapOperator::Hable { white: 11.2 };
+
+        assert_eq!(operator.map(&gray(0.0)), gray(0.0));
+        assert_eq!(operator.map(&gray(5.6)), gray(1.0));
+        assert_eq!(operator.map(&gray(50.0)), gray(1.0));
+
+        let mut previous = 0.0;
+        for i in 1..56 {
+            let value = operator.map(&gray(i as f64 / 10.0)).red;
+            assert!(value > previous);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn highlights_are_not_clipped() {
+        for operator in [
+            ToneMapOperator::Reinhard,
+            ToneMapOperator::ReinhardExtended { white: 16.0 },
+            ToneMapOperator::Aces,
+            ToneMapOperator::Hable { white: 11.2 },
+        ] {
+            let (bright, brighter) = (operator.map(&gray(2.0)), operator.map(&gray(4.0)));
+            assert!(bright.red < brighter.red, "{operator:?}");
+            assert!(brighter.red < 1.0, "{operator:?}");
+        }
+    }
+
+    #[test]
+    fn exposure_in_stops() {
+        let canvas = Canvas::new(2, 2);
+
+        assert_eq!(ToneMap::default().exposure_scale(&canvas), 1.0);
+        let tone_map = ToneMap::new(ToneMapOperator::Linear).with_exposure(2.0);
+        assert_eq!(tone_map.exposure_scale(&canvas), 4.0);
+        let tone_map = tone_map.with_exposure(-1.0);
+        assert_eq!(tone_map.exposure_scale(&canvas), 0.5);
+    }
+
+    #[test]
+    fn log_average_luminance() {
+        let mut canvas = Canvas::new(2, 1);
+        assert_eq!(Canvas::new(0, 3).log_average_luminance(), None);
+        assert!(canvas.log_average_luminance().unwrap() < 1e-3);
+
+        canvas.set_pixel_at(0, 0, &gray(0.5)).unwrap();
+        canvas.set_pixel_at(1, 0, &gray(8.0)).unwrap();
+        let average = canvas.log_average_luminance().unwrap();
+        assert!((average - 2.0).abs() < 1e-3);
+
+        // luminance weighs green the most
+        canvas
+            .set_pixel_at(0, 0, &Color::new(0.0, 1.0, 0.0))
+            .unwrap();
+        canvas
+            .set_pixel_at(1, 0, &Color::new(0.0, 1.0, 0.0))
+            .unwrap();
+        let average = canvas.log_average_luminance().unwrap();
+        assert!((average - 0.7152).abs() < 1e-3);
+    }
+
+    #[test]
+    fn auto_exposure_brings_average_to_key() {
+        let mut canvas = Canvas::new(3, 3);
+        for y in 0..3 {
+            for x in 0..3 {
+                canvas.set_pixel_at(x, y, &gray(5.0)).unwrap();
+            }
+        }
+        let tone_map = ToneMap::new(ToneMapOperator::Linear).with_auto_exposure(0.18);
+        let scale = tone_map.exposure_scale(&canvas);
+        assert!((scale * 5.0 - 0.18).abs() < 1e-3);
+
+        let mapped = canvas.tone_map(&tone_map);
+        let color = mapped.get_color_at(1, 1).unwrap();
+        assert!((color.red - 0.18).abs() < 1e-3);
+
+        // a black canvas has no exposure to find
+        assert_eq!(tone_map.exposure_scale(&Canvas::new(2, 2)), 1.0);
+    }
+
+    #[test]
+    fn tone_mapping_canvas() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.set_pixel_at(0, 0, &gray(1.0)).unwrap();
+        canvas
+            .set_pixel_at(1, 0, &Color::new(3.0, 3.0, 3.0))
+            .unwrap();
+
+        let mapped = canvas.tone_map(&ToneMap::new(ToneMapOperator::Reinhard).with_exposure(1.0));
+        assert_eq!((mapped.width(), mapped.height()), (2, 1));
+        assert_eq!(*mapped.get_color_at(0, 0).unwrap(), gray(2.0 / 3.0));
+        assert_eq!(*mapped.get_color_at(1, 0).unwrap(), gray(6.0 / 7.0));
+
+        // the default tone map only clamps, as the writers did before
+        let mapped = canvas.tone_map(&ToneMap::default());
+        assert_eq!(*mapped.get_color_at(1, 0).unwrap(), gray(1.0));
+        assert_eq!(mapped.to_ppm(), canvas.to_ppm());
+    }
+
+    #[test]
+    fn writers_apply_the_tone_map() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.set_pixel_at(0, 0, &gray(3.0)).unwrap();
+        let clamp = ToneMap::default();
+        let reinhard = ToneMap::new(ToneMapOperator::Reinhard);
+        let aces = ToneMap::new(ToneMapOperator::Aces);
+
+        // 0.75 and 0.954 of 255
+        assert_eq!(canvas.to_ppm_with(&clamp), "P3\n1 1\n255\n255 255 255\n");
+        assert_eq!(canvas.to_ppm_with(&reinhard), "P3\n1 1\n255\n191 191 191\n");
+        assert_eq!(canvas.to_ppm_with(&aces), "P3\n1 1\n255\n243 243 243\n");
+        assert_eq!(canvas.to_ppm_binary_with(&clamp)[11..], [255, 255, 255]);
+        assert_eq!(canvas.to_ppm_binary_with(&reinhard)[11..], [191, 191, 191]);
+
+        let mut ppm = vec![];
+        canvas
+            .write_ppm_with(&mut ppm, PpmFormat::Binary, 65535, &reinhard)
+            .unwrap();
+        assert_eq!(ppm[13..], [0xbf, 0xff, 0xbf, 0xff, 0xbf, 0xff]);
+
+        for (tone_map, expected) in [(clamp, 255.0), (reinhard, 191.0), (aces, 243.0)] {
+            let options = PngOptions {
+                tone_map,
+                ..PngOptions::new()
+            };
+            let mut png = vec![];
+            canvas.write_png(&mut png, &options).unwrap();
+            let read = Canvas::read_png(png.as_slice()).unwrap();
+            assert_eq!(*read.get_color_at(0, 0).unwrap(), gray(expected / 255.0));
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use crate::{EPSILON, ray::Ray, shape::Shape, tuple::Tuple};
+
+/// A single intersection between a ray and an object, located at distance
+/// t along the ray
+#[derive(Debug, Clone, Copy)]
+pub struct Intersection<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    /// Barycentric coordinates of the intersection on a triangle, used to
+    /// interpolate vertex normals. Zero for every other shape.
+    pub u: f64,
+    pub v: f64,
+}
+
+impl<'a> Intersection<'a> {
+    pub fn new(t: f64, object: &'a dyn Shape) -> Self {
+        Self::with_uv(t, object, 0.0, 0.0)
+    }
+
+    /// Creates an intersection on a triangle, at the barycentric
+    /// coordinates (u, v)
+    pub fn with_uv(t: f64, object: &'a dyn Shape, u: f64, v: f64) -> Self {
+        Self { t, object, u, v }
+    }
+
+    /// Precomputes the values needed to shade this intersection of the
+    /// given ray: the world-space point, the eye, normal and reflection
+    /// vectors, and whether the hit occurred inside the object. `xs` is the
+    /// sorted list of every intersection along the ray (including this
+    /// one), used to find the refractive indices on both sides of the
+    /// surface.
+    pub fn prepare_computations(&self, ray: &Ray, xs: &[Intersection<'a>]) -> Computations<'a> {
+        let point = ray.position(self.t);
+        let eyev = -ray.direction.clone();
+        let mut normalv = self.object.normal_at_hit(&point, self);
+
+        // when the normal points away from the eye, the hit is inside the
+        // object and the normal is flipped to point back at the eye
+        let inside = Tuple::dot(&normalv, &eyev) < 0.0;
+        if inside {
+            normalv = -normalv;
+        }
+
+        // nudge the point slightly above the surface so floating point
+        // errors don't make the surface shadow itself (shadow acne)
+        let over_point = &point + &normalv * EPSILON;
+        // and slightly below the surface, as origin of refracted rays
+        let under_point = &point - &normalv * EPSILON;
+        let reflectv = ray.direction.reflect(&normalv);
+        let (n1, n2) = self.refractive_indices(xs);
+
+        Computations {
+            t: self.t,
+            object: self.object,
+            point,
+            over_point,
+            under_point,
+            eyev,
+            normalv,
+            reflectv,
+            inside,
+            n1,
+            n2,
+        }
+    }
+
+    /// Finds the refractive indices of the materials being exited (n1) and
+    /// entered (n2) at this intersection, by tracking which objects
+    /// contain the ray as it walks along the intersections
+    fn refractive_indices(&self, xs: &[Intersection<'a>]) -> (f64, f64) {
+        let mut containers: Vec<&dyn Shape> = vec![];
+        let mut n1 = 1.0;
+        let mut n2 = 1.0;
+
+        for i in xs {
+            let is_hit = i.t == self.t && std::ptr::addr_eq(i.object, self.object);
+
+            if is_hit {
+                n1 = containers
+                    .last()
+                    .map_or(1.0, |object| object.material().refractive_index);
+            }
+
+            match containers
+                .iter()
+                .position(|object| std::ptr::addr_eq(*object, i.object))
+            {
+                Some(index) => {
+                    containers.remove(index);
+                }
+                None => containers.push(i.object),
+            }
+
+            if is_hit {
+                n2 = containers
+                    .last()
+                    .map_or(1.0, |object| object.material().refractive_index);
+                break;
+            }
+        }
+        (n1, n2)
+    }
+}
+
+/// Precomputed state of an intersection, used for shading
+#[derive(Debug, Clone)]
+pub struct Computations<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    pub point: Tuple,
+    /// The hit point offset slightly along the normal, used as the origin
+    /// of rays leaving the surface
+    pub over_point: Tuple,
+    /// The hit point offset slightly below the surface, used as the origin
+    /// of refracted rays
+    pub under_point: Tuple,
+    pub eyev: Tuple,
+    pub normalv: Tuple,
+    pub reflectv: Tuple,
+    pub inside: bool,
+    /// Refractive index of the material being exited
+    pub n1: f64,
+    /// Refractive index of the material being entered
+    pub n2: f64,
+}
+
+impl Computations<'_> {
+    /// Approximates the Fresnel effect with Schlick's formula, returning the
+    /// fraction of light reflected (rather than refracted) at the surface
+    pub fn schlick(&self) -> f64 {
+        // cosine of the angle between the eye and the normal
+        let mut cos = Tuple::dot(&self.eyev, &self.normalv);
+
+        // total internal reflection can only occur if n1 > n2
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n * n * (1.0 - cos * cos);
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            // when n1 > n2, use cos(theta_t) instead
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+/// Collects intersections into a list sorted by increasing t
+pub fn intersections(mut xs: Vec<Intersection>) -> Vec<Intersection> {
+    xs.sort_by(|a, b| a.t.total_cmp(&b.t));
+    xs
+}
+
+/// Returns the visible intersection from a list of intersections, which is
+/// the one with the lowest non-negative t. Intersections behind the ray
+/// origin (negative t) are ignored.
+pub fn hit<'a, 'b>(xs: &'b [Intersection<'a>]) -> Option<&'b Intersection<'a>> {
+    xs.iter()
+        .filter(|i| i.t >= 0.0)
+        .min_by(|a, b| a.t.total_cmp(&b.t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shape::{Plane, Shape, Sphere, Triangle},
+        transform,
+    };
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn an_intersection_encapsulates_t_and_object() {
+        let sphere = Sphere::new();
+        let i = Intersection::new(3.5, &sphere);
+
+        assert_eq!(i.t, 3.5);
+        assert!(std::ptr::addr_eq(i.object, &sphere));
+    }
+
+    #[test]
+    // An intersection can encapsulate u and v
+    fn an_intersection_encapsulates_u_and_v() {
+        let triangle = Triangle::new(
+            Tuple::new_point(0.0, 1.0, 0.0),
+            Tuple::new_point(-1.0, 0.0, 0.0),
+            Tuple::new_point(1.0, 0.0, 0.0),
+        );
+        let i = Intersection::with_uv(3.5, &triangle, 0.2, 0.4);
+
+        assert_eq!(i.u, 0.2);
+        assert_eq!(i.v, 0.4);
+    }
+
+    #[test]
+    fn aggregating_intersections() {
+        let sphere = Sphere::new();
+        let xs = intersections(vec![
+            Intersection::new(2.0, &sphere),
+            Intersection::new(1.0, &sphere),
+        ]);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 1.0);
+        assert_eq!(xs[1].t, 2.0);
+    }
+
+    #[test]
+    // The hit, when all intersections have positive t
+    fn hit_all_positive() {
+        let sphere = Sphere::new();
+        let xs = [
+            Intersection::new(1.0, &sphere),
+            Intersection::new(2.0, &sphere),
+        ];
+
+        assert_eq!(hit(&xs).map(|i| i.t), Some(1.0));
+    }
+
+    #[test]
+    // The hit, when some intersections have negative t
+    fn hit_some_negative() {
+        let sphere = Sphere::new();
+        let xs = [
+            Intersection::new(-1.0, &sphere),
+            Intersection::new(1.0, &sphere),
+        ];
+
+        assert_eq!(hit(&xs).map(|i| i.t), Some(1.0));
+    }
+
+    #[test]
+    // The hit, when all intersections have negative t
+    fn hit_all_negative() {
+        let sphere = Sphere::new();
+        let xs = [
+            Intersection::new(-2.0, &sphere),
+            Intersection::new(-1.0, &sphere),
+        ];
+
+        assert!(hit(&xs).is_none());
+    }
+
+    #[test]
+    // The hit is always the lowest nonnegative intersection
+    fn hit_lowest_nonnegative() {
+        let sphere = Sphere::new();
+        let xs = [
+            Intersection::new(5.0, &sphere),
+            Intersection::new(7.0, &sphere),
+            Intersection::new(-3.0, &sphere),
+            Intersection::new(2.0, &sphere),
+        ];
+
+        assert_eq!(hit(&xs).map(|i| i.t), Some(2.0));
+    }
+
+    #[test]
+    fn precomputing_state_of_an_intersection() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let i = Intersection::new(4.0, &sphere);
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(comps.t, i.t);
+        assert!(std::ptr::addr_eq(comps.object, i.object));
+        assert_eq!(comps.point, Tuple::new_point(0.0, 0.0, -1.0));
+        assert_eq!(comps.eyev, Tuple::new_vector(0.0, 0.0, -1.0));
+        assert_eq!(comps.normalv, Tuple::new_vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    // The hit, when an intersection occurs on the outside
+    fn hit_occurs_on_the_outside() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let i = Intersection::new(4.0, &sphere);
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert!(!comps.inside);
+    }
+
+    #[test]
+    // The hit, when an intersection occurs on the inside
+    fn hit_occurs_on_the_inside() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let i = Intersection::new(1.0, &sphere);
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(comps.point, Tuple::new_point(0.0, 0.0, 1.0));
+        assert_eq!(comps.eyev, Tuple::new_vector(0.0, 0.0, -1.0));
+        assert!(comps.inside);
+        // normal would have been (0, 0, 1), but is inverted
+        assert_eq!(comps.normalv, Tuple::new_vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn hit_should_offset_the_point() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &sphere);
+        let comps = i.prepare_computations(&ray, &[i]);
+        let (_, _, over_z, _) = comps.over_point.get();
+        let (_, _, z, _) = comps.point.get();
+
+        assert!(over_z < -EPSILON / 2.0);
+        assert!(z > over_z);
+    }
+
+    #[test]
+    fn precomputing_the_reflection_vector() {
+        let shape = Plane::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 1.0, -1.0),
+            Tuple::new_vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(f64::sqrt(2.0), &shape);
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            comps.reflectv,
+            Tuple::new_vector(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    // Finding n1 and n2 at various intersections
+    fn finding_n1_and_n2() {
+        let mut a = Sphere::glass();
+        a.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let mut material = a.material().clone();
+        material.refractive_index = 1.5;
+        a.set_material(material);
+
+        let mut b = Sphere::glass();
+        b.set_transform(transform::translation(0.0, 0.0, -0.25));
+        let mut material = b.material().clone();
+        material.refractive_index = 2.0;
+        b.set_material(material);
+
+        let mut c = Sphere::glass();
+        c.set_transform(transform::translation(0.0, 0.0, 0.25));
+        let mut material = c.material().clone();
+        material.refractive_index = 2.5;
+        c.set_material(material);
+
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -4.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = intersections(vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ]);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+
+        for (i, (n1, n2)) in expected.iter().enumerate() {
+            let comps = xs[i].prepare_computations(&ray, &xs);
+            assert_eq!(comps.n1, *n1);
+            assert_eq!(comps.n2, *n2);
+        }
+    }
+
+    #[test]
+    // The under point is offset below the surface
+    fn under_point_is_offset_below_surface() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut shape = Sphere::glass();
+        shape.set_transform(transform::translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&ray, &[i]);
+        let (_, _, under_z, _) = comps.under_point.get();
+        let (_, _, z, _) = comps.point.get();
+
+        assert!(under_z > EPSILON / 2.0);
+        assert!(z < under_z);
+    }
+
+    #[test]
+    // The Schlick approximation under total internal reflection
+    fn schlick_under_total_internal_reflection() {
+        let shape = Sphere::glass();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, FRAC_1_SQRT_2),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let xs = [
+            Intersection::new(-FRAC_1_SQRT_2, &shape),
+            Intersection::new(FRAC_1_SQRT_2, &shape),
+        ];
+        let comps = xs[1].prepare_computations(&ray, &xs);
+
+        assert_eq!(comps.schlick(), 1.0);
+    }
+
+    #[test]
+    // The Schlick approximation with a perpendicular viewing angle
+    fn schlick_with_perpendicular_viewing_angle() {
+        let shape = Sphere::glass();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let xs = [
+            Intersection::new(-1.0, &shape),
+            Intersection::new(1.0, &shape),
+        ];
+        let comps = xs[1].prepare_computations(&ray, &xs);
+
+        assert!((comps.schlick() - 0.04).abs() < EPSILON);
+    }
+
+    #[test]
+    // The Schlick approximation with small angle and n2 > n1
+    fn schlick_with_small_angle_and_n2_greater_than_n1() {
+        let shape = Sphere::glass();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.99, -2.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = [Intersection::new(1.8589, &shape)];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        assert!((comps.schlick() - 0.48873).abs() < EPSILON);
+    }
+}
@@ -1,6 +1,18 @@
+pub mod bounds;
+pub mod bvh;
 pub mod draw;
 pub mod tuple;
 pub mod matrix;
 pub mod transform;
+pub mod ray;
+pub mod intersection;
+pub mod shape;
+pub mod material;
+pub mod pattern;
+pub mod light;
+pub mod world;
+pub mod camera;
+pub mod render;
+pub mod obj;
 
 const EPSILON: f64 = 0.00001;
@@ -0,0 +1,222 @@
+use crate::{draw::Color, material::Material, shape::Shape, tuple::Tuple};
+
+/// A light source with no size, existing at a single point in space
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointLight {
+    pub position: Tuple,
+    pub intensity: Color,
+    /// Whether objects occluding this light cast shadows. When false, the
+    /// light illuminates every surface facing it.
+    pub casts_shadows: bool,
+}
+
+impl PointLight {
+    /// Creates a point light which casts shadows
+    pub fn new(position: Tuple, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+            casts_shadows: true,
+        }
+    }
+}
+
+/// Computes the color of a point on a surface using the Phong reflection
+/// model, which is the sum of the ambient, diffuse and specular
+/// contributions of the light. `eyev` and `normalv` are expected to be
+/// normalized vectors. A point in shadow only receives ambient light.
+/// The object is needed to evaluate the material's pattern, if any.
+pub fn lighting(
+    material: &Material,
+    object: &dyn Shape,
+    light: &PointLight,
+    point: &Tuple,
+    eyev: &Tuple,
+    normalv: &Tuple,
+    in_shadow: bool,
+) -> Color {
+    let color = match &material.pattern {
+        Some(pattern) => pattern.pattern_at_shape(object, point),
+        None => material.color.clone(),
+    };
+
+    // combine the surface color with the light's color/intensity
+    let effective_color = Color::hadamard_product(&color, &light.intensity);
+    let lightv = (&light.position - point).normalize();
+    let ambient = effective_color.clone() * material.ambient;
+    if in_shadow {
+        return ambient;
+    }
+
+    // light_dot_normal is the cosine of the angle between the light vector
+    // and the normal vector. A negative number means the light is on the
+    // other side of the surface.
+    let light_dot_normal = Tuple::dot(&lightv, normalv);
+    if light_dot_normal < 0.0 {
+        return ambient;
+    }
+    let diffuse = effective_color * material.diffuse * light_dot_normal;
+
+    // reflect_dot_eye is the cosine of the angle between the reflection
+    // vector and the eye vector. A negative number means the light reflects
+    // away from the eye.
+    let reflectv = (-lightv).reflect(normalv);
+    let reflect_dot_eye = Tuple::dot(&reflectv, eyev);
+    let specular = if reflect_dot_eye <= 0.0 {
+        Color::new(0.0, 0.0, 0.0)
+    } else {
+        let factor = reflect_dot_eye.powf(material.shininess);
+        light.intensity.clone() * material.specular * factor
+    };
+
+    ambient + diffuse + specular
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pattern::Stripe, shape::Sphere};
+    use std::sync::Arc;
+
+    #[test]
+    fn point_light_has_position_and_intensity() {
+        let intensity = Color::new(1.0, 1.0, 1.0);
+        let position = Tuple::new_point(0.0, 0.0, 0.0);
+        let light = PointLight::new(position.clone(), intensity.clone());
+
+        assert_eq!(light.position, position);
+        assert_eq!(light.intensity, intensity);
+    }
+
+    fn setup() -> (Material, Sphere, Tuple) {
+        (
+            Material::new(),
+            Sphere::new(),
+            Tuple::new_point(0.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    // Lighting with the eye between the light and the surface
+    fn eye_between_light_and_surface() {
+        let (material, object, position) = setup();
+        let eyev = Tuple::new_vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::new_point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let result = lighting(
+            &material, &object, &light, &position, &eyev, &normalv, false,
+        );
+        assert_eq!(result, Color::new(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    // Lighting with the eye between light and surface, eye offset 45°
+    fn eye_offset_45_degrees() {
+        let (material, object, position) = setup();
+        let eyev = Tuple::new_vector(0.0, f64::sqrt(2.0) / 2.0, -f64::sqrt(2.0) / 2.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::new_point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let result = lighting(
+            &material, &object, &light, &position, &eyev, &normalv, false,
+        );
+        assert_eq!(result, Color::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    // Lighting with eye opposite surface, light offset 45°
+    fn light_offset_45_degrees() {
+        let (material, object, position) = setup();
+        let eyev = Tuple::new_vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(
+            Tuple::new_point(0.0, 10.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        );
+
+        let result = lighting(
+            &material, &object, &light, &position, &eyev, &normalv, false,
+        );
+        assert_eq!(result, Color::new(0.7364, 0.7364, 0.7364));
+    }
+
+    #[test]
+    // Lighting with eye in the path of the reflection vector
+    fn eye_in_path_of_reflection_vector() {
+        let (material, object, position) = setup();
+        let eyev = Tuple::new_vector(0.0, -f64::sqrt(2.0) / 2.0, -f64::sqrt(2.0) / 2.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(
+            Tuple::new_point(0.0, 10.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        );
+
+        let result = lighting(
+            &material, &object, &light, &position, &eyev, &normalv, false,
+        );
+        assert_eq!(result, Color::new(1.6364, 1.6364, 1.6364));
+    }
+
+    #[test]
+    // Lighting with the light behind the surface
+    fn light_behind_surface() {
+        let (material, object, position) = setup();
+        let eyev = Tuple::new_vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::new_point(0.0, 0.0, 10.0), Color::new(1.0, 1.0, 1.0));
+
+        let result = lighting(
+            &material, &object, &light, &position, &eyev, &normalv, false,
+        );
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_surface_in_shadow() {
+        let (material, object, position) = setup();
+        let eyev = Tuple::new_vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::new_point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let result = lighting(&material, &object, &light, &position, &eyev, &normalv, true);
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_pattern_applied() {
+        let (mut material, object, _) = setup();
+        material.pattern = Some(Arc::new(Stripe::new(
+            Color::new(1.0, 1.0, 1.0),
+            Color::new(0.0, 0.0, 0.0),
+        )));
+        material.ambient = 1.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        let eyev = Tuple::new_vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::new_vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::new_point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let c1 = lighting(
+            &material,
+            &object,
+            &light,
+            &Tuple::new_point(0.9, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            false,
+        );
+        let c2 = lighting(
+            &material,
+            &object,
+            &light,
+            &Tuple::new_point(1.1, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            false,
+        );
+
+        assert_eq!(c1, Color::new(1.0, 1.0, 1.0));
+        assert_eq!(c2, Color::new(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{draw::Color, pattern::Pattern};
+use std::{cmp, sync::Arc};
+
+/// Surface attributes used by the Phong reflection model
+#[derive(Debug, Clone)]
+pub struct Material {
+    pub color: Color,
+    /// When set, the pattern gives the surface color instead of `color`
+    pub pattern: Option<Arc<dyn Pattern>>,
+    pub ambient: f64,
+    pub diffuse: f64,
+    pub specular: f64,
+    pub shininess: f64,
+    /// 0 for a non-reflective surface, 1 for a perfect mirror
+    pub reflective: f64,
+    /// 0 for an opaque surface, 1 for a perfectly transparent one
+    pub transparency: f64,
+    /// How much light bends when entering the material: 1.0 for vacuum,
+    /// 1.33 for water, 1.52 for glass, 2.42 for diamond
+    pub refractive_index: f64,
+}
+
+impl Material {
+    /// Creates the default material: white, with ambient = 0.1,
+    /// diffuse = 0.9, specular = 0.9 and shininess = 200, neither
+    /// reflective nor transparent
+    pub fn new() -> Self {
+        Self {
+            color: Color::new(1.0, 1.0, 1.0),
+            pattern: None,
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.9,
+            shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+        }
+    }
+}
+
+impl cmp::PartialEq for Material {
+    /// Materials are equal when their attributes are equal and they share
+    /// the same pattern (if any)
+    fn eq(&self, rhs: &Self) -> bool {
+        let same_pattern = match (&self.pattern, &rhs.pattern) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        };
+        same_pattern
+            && self.color == rhs.color
+            && self.ambient == rhs.ambient
+            && self.diffuse == rhs.diffuse
+            && self.specular == rhs.specular
+            && self.shininess == rhs.shininess
+            && self.reflective == rhs.reflective
+            && self.transparency == rhs.transparency
+            && self.refractive_index == rhs.refractive_index
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_material() {
+        let material = Material::new();
+
+        assert_eq!(material.color, Color::new(1.0, 1.0, 1.0));
+        assert!(material.pattern.is_none());
+        assert_eq!(material.ambient, 0.1);
+        assert_eq!(material.diffuse, 0.9);
+        assert_eq!(material.specular, 0.9);
+        assert_eq!(material.shininess, 200.0);
+        assert_eq!(material.reflective, 0.0);
+        assert_eq!(material.transparency, 0.0);
+        assert_eq!(material.refractive_index, 1.0);
+    }
+}
@@ -135,7 +135,7 @@ impl Matrix2 {
     }
 
     pub fn inverse(self) -> Option<Self> {
-        if let false = self.invertible() {
+        if !self.invertible() {
             None
         } else {
             let det = self.det();
@@ -175,7 +175,7 @@ impl ops::Add<&Self> for Matrix2 {
         Self::add(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Add<&'b Matrix2> for &'a Matrix2 {
+impl<'b> ops::Add<&'b Matrix2> for &Matrix2 {
     type Output = Matrix2;
     fn add(self, rhs: &'b Matrix2) -> Self::Output {
         Matrix2::add(self, rhs)
@@ -194,7 +194,7 @@ impl ops::Sub<&Self> for Matrix2 {
         Self::sub(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Sub<&'b Matrix2> for &'a Matrix2 {
+impl<'b> ops::Sub<&'b Matrix2> for &Matrix2 {
     type Output = Matrix2;
     fn sub(self, rhs: &'b Matrix2) -> Self::Output {
         Matrix2::sub(self, rhs)
@@ -213,7 +213,7 @@ impl ops::Mul<&Self> for Matrix2 {
         Self::mult_mat(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Mul<&'b Matrix2> for &'a Matrix2 {
+impl<'b> ops::Mul<&'b Matrix2> for &Matrix2 {
     type Output = Matrix2;
     fn mul(self, rhs: &'b Matrix2) -> Self::Output {
         Matrix2::mult_mat(self, rhs)
@@ -191,7 +191,7 @@ impl Matrix3 {
     }
 
     pub fn inverse(self) -> Option<Self> {
-        if let false = self.invertible() {
+        if !self.invertible() {
             None
         } else {
             let adjoint = Self::from_array(core::array::from_fn(|i| {
@@ -233,7 +233,7 @@ impl ops::Add<&Self> for Matrix3 {
         Self::add(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Add<&'b Matrix3> for &'a Matrix3 {
+impl<'b> ops::Add<&'b Matrix3> for &Matrix3 {
     type Output = Matrix3;
     fn add(self, rhs: &'b Matrix3) -> Self::Output {
         Matrix3::add(self, rhs)
@@ -252,7 +252,7 @@ impl ops::Sub<&Self> for Matrix3 {
         Self::sub(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Sub<&'b Matrix3> for &'a Matrix3 {
+impl<'b> ops::Sub<&'b Matrix3> for &Matrix3 {
     type Output = Matrix3;
     fn sub(self, rhs: &'b Matrix3) -> Self::Output {
         Matrix3::sub(self, rhs)
@@ -271,7 +271,7 @@ impl ops::Mul<&Self> for Matrix3 {
         Self::mult_mat(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Mul<&'b Matrix3> for &'a Matrix3 {
+impl<'b> ops::Mul<&'b Matrix3> for &Matrix3 {
     type Output = Matrix3;
     fn mul(self, rhs: &'b Matrix3) -> Self::Output {
         Matrix3::mult_mat(self, rhs)
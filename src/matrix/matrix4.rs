@@ -269,7 +269,7 @@ impl Matrix4 {
 
     /// Return the inverse of the matrix
     pub fn inverse(self) -> Option<Self> {
-        if let false = self.invertible() {
+        if !self.invertible() {
             None
         } else {
             let adjoint = Self::from_array(core::array::from_fn(|i| {
@@ -347,7 +347,7 @@ impl ops::Add<&Self> for Matrix4 {
         Self::add(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Add<&'b Matrix4> for &'a Matrix4 {
+impl<'b> ops::Add<&'b Matrix4> for &Matrix4 {
     type Output = Matrix4;
     fn add(self, rhs: &'b Matrix4) -> Self::Output {
         Matrix4::add(self, rhs)
@@ -366,7 +366,7 @@ impl ops::Sub<&Self> for Matrix4 {
         Self::sub(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Sub<&'b Matrix4> for &'a Matrix4 {
+impl<'b> ops::Sub<&'b Matrix4> for &Matrix4 {
     type Output = Matrix4;
     fn sub(self, rhs: &'b Matrix4) -> Self::Output {
         Matrix4::sub(self, rhs)
@@ -385,7 +385,7 @@ impl ops::Mul<&Self> for Matrix4 {
         Self::mult_mat(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Mul<&'b Matrix4> for &'a Matrix4 {
+impl<'b> ops::Mul<&'b Matrix4> for &Matrix4 {
     type Output = Matrix4;
     fn mul(self, rhs: &'b Matrix4) -> Self::Output {
         Matrix4::mult_mat(self, rhs)
@@ -404,7 +404,7 @@ impl ops::Mul<&Tuple> for Matrix4 {
         Self::mult_vec(&self, rhs)
     }
 }
-impl<'a, 'b> ops::Mul<&'b Tuple> for &'a Matrix4 {
+impl<'b> ops::Mul<&'b Tuple> for &Matrix4 {
     type Output = Tuple;
     fn mul(self, rhs: &'b Tuple) -> Self::Output {
         Matrix4::mult_vec(self, rhs)
@@ -591,7 +591,7 @@ mod tests {
         let matrix_a = Matrix4::from_array([
             -5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0, 4.0,
         ]);
-        let matrix_b = (&matrix_a).clone().inverse().unwrap();
+        let matrix_b = matrix_a.clone().inverse().unwrap();
         let det_a = matrix_a.det();
 
         assert_eq!(&matrix_a * &matrix_b, Matrix4::identity());
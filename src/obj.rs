@@ -0,0 +1,772 @@
+mod mtl;
+
+pub use mtl::MtlLibrary;
+
+use crate::{
+    material::Material,
+    shape::{Group, Shape, SmoothTriangle, Triangle},
+    tuple::Tuple,
+};
+use std::{
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Error raised while loading an OBJ or MTL file. Parsing errors carry the
+/// (1-indexed) line they occurred on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjError {
+    /// A statement has fewer values than it requires
+    MissingValues {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A value could not be parsed as a number
+    InvalidNumber { line: usize, value: String },
+    /// A face refers to a vertex, normal or texture coordinate that doesn't
+    /// exist (OBJ indices start at 1, negative indices count from the end)
+    InvalidIndex { line: usize, index: i64 },
+    /// A material statement of an MTL file appears before any `newmtl`
+    MaterialNotDeclared { line: usize },
+    /// A file could not be read
+    Io { path: PathBuf, kind: io::ErrorKind },
+    /// A material library referenced by `mtllib` could not be parsed
+    InLibrary { path: PathBuf, error: Box<ObjError> },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::MissingValues {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: expected at least {expected} values, found {found}"
+            ),
+            ObjError::InvalidNumber { line, value } => {
+                write!(f, "line {line}: invalid number {value:?}")
+            }
+            ObjError::InvalidIndex { line, index } => {
+                write!(f, "line {line}: invalid index {index}")
+            }
+            ObjError::MaterialNotDeclared { line } => {
+                write!(f, "line {line}: material statement before any newmtl")
+            }
+            ObjError::Io { path, kind } => write!(f, "{}: {kind}", path.display()),
+            ObjError::InLibrary { path, error } => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl error::Error for ObjError {}
+
+/// A line of an OBJ or MTL file that was not understood, and skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoredLine {
+    pub line: usize,
+    pub text: String,
+    /// The material library the line comes from, when it was loaded through
+    /// `mtllib`, or `None` for a line of the file being parsed
+    pub path: Option<PathBuf>,
+}
+
+/// The content of a Wavefront OBJ file: its vertex data, and the triangles
+/// built from its faces. Faces are fan-triangulated, and become smooth
+/// triangles when every one of their vertices has a normal. Triangles get
+/// the material last selected with `usemtl`, or the default material.
+#[derive(Debug, Default)]
+pub struct ObjFile {
+    vertices: Vec<Tuple>,
+    normals: Vec<Tuple>,
+    texture_coords: Vec<(f64, f64)>,
+    default_group: Group,
+    groups: Vec<(String, Group)>,
+    materials: MtlLibrary,
+    ignored: Vec<IgnoredLine>,
+}
+
+/// References to the vertex data of one corner of a face, as 0-indexed
+/// positions in the vertex lists
+struct FaceVertex {
+    vertex: usize,
+    normal: Option<usize>,
+}
+
+impl ObjFile {
+    /// Parses the content of an OBJ file. Supports vertices (`v`), normals
+    /// (`vn`), texture coordinates (`vt`), faces (`f`) and named groups or
+    /// objects (`g`, `o`); any other statement is recorded as ignored.
+    ///
+    /// Material libraries can't be read without knowing where the file
+    /// is, so `mtllib` statements are ignored, and so are `usemtl`
+    /// statements: use `parse_with_materials` or `load` instead.
+    pub fn parse(input: &str) -> Result<Self, ObjError> {
+        Self::parse_from(input, MtlLibrary::new(), None)
+    }
+
+    /// Parses the content of an OBJ file, with `usemtl` statements
+    /// selecting materials from the given library. `mtllib` statements are
+    /// ignored.
+    pub fn parse_with_materials(input: &str, materials: MtlLibrary) -> Result<Self, ObjError> {
+        Self::parse_from(input, materials, None)
+    }
+
+    /// Reads and parses an OBJ file, along with the material libraries it
+    /// references through `mtllib` (relative to the OBJ file's directory)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ObjError> {
+        let path = path.as_ref();
+        let input = read_file(path)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        Self::parse_from(&input, MtlLibrary::new(), Some(directory))
+    }
+
+    /// Parses an OBJ file with an initial material library. When
+    /// `directory` is given, libraries referenced with `mtllib` are loaded
+    /// from it.
+    fn parse_from(
+        input: &str,
+        materials: MtlLibrary,
+        directory: Option<&Path>,
+    ) -> Result<Self, ObjError> {
+        let mut obj = Self {
+            materials,
+            ..Self::new()
+        };
+        // index into `groups` of the group faces currently go to, if any
+        let mut current_group: Option<usize> = None;
+        let mut current_material: Option<Material> = None;
+
+        for (index, text) in input.lines().enumerate() {
+            let line = index + 1;
+            let mut words = text.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let args: Vec<&str> = words.collect();
+
+            match keyword {
+                _ if keyword.starts_with('#') => {}
+                "v" => {
+                    let [x, y, z] = parse_numbers(line, &args)?;
+                    obj.vertices.push(Tuple::new_point(x, y, z));
+                }
+                "vn" => {
+                    let [x, y, z] = parse_numbers(line, &args)?;
+                    obj.normals.push(Tuple::new_vector(x, y, z));
+                }
+                "vt" => {
+                    let [u] = parse_numbers(line, &args)?;
+                    let v = match args.get(1) {
+                        Some(value) => parse_number(line, value)?,
+                        None => 0.0,
+                    };
+                    obj.texture_coords.push((u, v));
+                }
+                "f" => {
+                    let triangles = obj.parse_face(line, &args)?;
+                    let group = match current_group {
+                        Some(index) => &mut obj.groups[index].1,
+                        None => &mut obj.default_group,
+                    };
+                    for mut triangle in triangles {
+                        if let Some(material) = &current_material {
+                            triangle.set_material(material.clone());
+                        }
+                        group.add_child(triangle);
+                    }
+                }
+                "mtllib" => match directory {
+                    Some(directory) => obj.load_libraries(directory, &args)?,
+                    None => obj.ignore(line, text),
+                },
+                // an unknown material leaves the faces with the default one
+                "usemtl" => match obj.materials.get(&args.join(" ")) {
+                    Some(material) => current_material = Some(material.clone()),
+                    None => {
+                        current_material = None;
+                        obj.ignore(line, text);
+                    }
+                },
+                "g" | "o" if !args.is_empty() => {
+                    let name = args.join(" ");
+                    let index = match obj.groups.iter().position(|(n, _)| *n == name) {
+                        Some(index) => index,
+                        None => {
+                            obj.groups.push((name, Group::new()));
+                            obj.groups.len() - 1
+                        }
+                    };
+                    current_group = Some(index);
+                }
+                _ => obj.ignore(line, text),
+            }
+        }
+
+        Ok(obj)
+    }
+
+    fn ignore(&mut self, line: usize, text: &str) {
+        self.ignored.push(IgnoredLine {
+            line,
+            text: text.to_string(),
+            path: None,
+        });
+    }
+
+    /// Loads the material libraries with the given file names, relative to
+    /// `directory`. Their ignored lines are reported with the file's own,
+    /// tagged with the path of the library.
+    fn load_libraries(&mut self, directory: &Path, names: &[&str]) -> Result<(), ObjError> {
+        for name in names {
+            let path = directory.join(name);
+            let mut library =
+                MtlLibrary::parse(&read_file(&path)?).map_err(|error| ObjError::InLibrary {
+                    path: path.clone(),
+                    error: Box::new(error),
+                })?;
+            // moved out of the library, so that only the materials are merged
+            self.ignored.extend(
+                library
+                    .take_ignored()
+                    .into_iter()
+                    .map(|ignored| IgnoredLine {
+                        path: Some(path.clone()),
+                        ..ignored
+                    }),
+            );
+            self.materials.extend(library);
+        }
+        Ok(())
+    }
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the vertex at the given (1-indexed) position
+    pub fn vertex(&self, index: usize) -> Option<&Tuple> {
+        self.vertices.get(index.checked_sub(1)?)
+    }
+
+    /// Returns the normal at the given (1-indexed) position
+    pub fn normal(&self, index: usize) -> Option<&Tuple> {
+        self.normals.get(index.checked_sub(1)?)
+    }
+
+    /// Returns the texture coordinates (u, v) at the given (1-indexed)
+    /// position
+    pub fn texture_coord(&self, index: usize) -> Option<(f64, f64)> {
+        self.texture_coords.get(index.checked_sub(1)?).copied()
+    }
+
+    /// Returns the group holding the faces declared outside of any named
+    /// group
+    pub fn default_group(&self) -> &Group {
+        &self.default_group
+    }
+
+    /// Returns the group declared with the given name (`g` or `o`)
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, group)| group)
+    }
+
+    /// Returns the materials available to `usemtl`
+    pub fn materials(&self) -> &MtlLibrary {
+        &self.materials
+    }
+
+    /// Returns the lines that were skipped as unrecognized
+    pub fn ignored(&self) -> &[IgnoredLine] {
+        &self.ignored
+    }
+
+    /// Converts the file into a single group: the faces of the default group
+    /// are its direct children, and each named group is added as a child
+    /// group
+    pub fn into_group(self) -> Group {
+        let mut group = self.default_group;
+        for (_, named) in self.groups {
+            group.add_child(Box::new(named));
+        }
+        group
+    }
+
+    /// Builds the triangles of a face, fanning out from its first vertex
+    fn parse_face(&self, line: usize, args: &[&str]) -> Result<Vec<Box<dyn Shape>>, ObjError> {
+        if args.len() < 3 {
+            return Err(ObjError::MissingValues {
+                line,
+                expected: 3,
+                found: args.len(),
+            });
+        }
+
+        let corners = args
+            .iter()
+            .map(|arg| self.parse_face_vertex(line, arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let smooth = corners.iter().all(|corner| corner.normal.is_some());
+
+        let triangles = (1..corners.len() - 1)
+            .map(|i| {
+                let (a, b, c) = (&corners[0], &corners[i], &corners[i + 1]);
+                let p1 = self.vertices[a.vertex].clone();
+                let p2 = self.vertices[b.vertex].clone();
+                let p3 = self.vertices[c.vertex].clone();
+
+                let triangle: Box<dyn Shape> = match (a.normal, b.normal, c.normal) {
+                    (Some(n1), Some(n2), Some(n3)) if smooth => Box::new(SmoothTriangle::new(
+                        p1,
+                        p2,
+                        p3,
+                        self.normals[n1].clone(),
+                        self.normals[n2].clone(),
+                        self.normals[n3].clone(),
+                    )),
+                    _ => Box::new(Triangle::new(p1, p2, p3)),
+                };
+                triangle
+            })
+            .collect();
+
+        Ok(triangles)
+    }
+
+    /// Parses one corner of a face, in any of the `v`, `v/vt`, `v//vn` or
+    /// `v/vt/vn` forms
+    fn parse_face_vertex(&self, line: usize, arg: &str) -> Result<FaceVertex, ObjError> {
+        let mut parts = arg.split('/');
+        let vertex = resolve_index(line, parts.next().unwrap_or(""), self.vertices.len())?;
+
+        if let Some(texture) = parts.next().filter(|part| !part.is_empty()) {
+            resolve_index(line, texture, self.texture_coords.len())?;
+        }
+
+        let normal = match parts.next().filter(|part| !part.is_empty()) {
+            Some(normal) => Some(resolve_index(line, normal, self.normals.len())?),
+            None => None,
+        };
+
+        Ok(FaceVertex { vertex, normal })
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|error| ObjError::Io {
+        path: path.to_path_buf(),
+        kind: error.kind(),
+    })
+}
+
+fn parse_number(line: usize, value: &str) -> Result<f64, ObjError> {
+    value.parse().map_err(|_| ObjError::InvalidNumber {
+        line,
+        value: value.to_string(),
+    })
+}
+
+/// Parses the first N arguments as numbers. Extra arguments (such as the
+/// optional w of a vertex) are not used.
+fn parse_numbers<const N: usize>(line: usize, args: &[&str]) -> Result<[f64; N], ObjError> {
+    if args.len() < N {
+        return Err(ObjError::MissingValues {
+            line,
+            expected: N,
+            found: args.len(),
+        });
+    }
+
+    let mut numbers = [0.0; N];
+    for (number, arg) in numbers.iter_mut().zip(args) {
+        *number = parse_number(line, arg)?;
+    }
+    Ok(numbers)
+}
+
+/// Converts a 1-indexed (or negative, counting back from the last element)
+/// OBJ index into a 0-indexed position in a list of `len` elements
+fn resolve_index(line: usize, value: &str, len: usize) -> Result<usize, ObjError> {
+    let index: i64 = value.parse().map_err(|_| ObjError::InvalidNumber {
+        line,
+        value: value.to_string(),
+    })?;
+    let len = len as i64;
+
+    let position = if index < 0 { len + index } else { index - 1 };
+    if index == 0 || position < 0 || position >= len {
+        return Err(ObjError::InvalidIndex { line, index });
+    }
+    Ok(position as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::Color;
+    use std::any::Any;
+
+    fn as_triangle(shape: &dyn Shape) -> &Triangle {
+        (shape as &dyn Any)
+            .downcast_ref()
+            .expect("Shape should be a triangle")
+    }
+
+    fn as_smooth_triangle(shape: &dyn Shape) -> &SmoothTriangle {
+        (shape as &dyn Any)
+            .downcast_ref()
+            .expect("Shape should be a smooth triangle")
+    }
+
+    #[test]
+    fn ignoring_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+        let obj = ObjFile::parse(gibberish).unwrap();
+
+        assert_eq!(obj.ignored().len(), 5);
+        assert_eq!(obj.ignored()[1].line, 2);
+        assert_eq!(
+            obj.ignored()[1].text,
+            "who traveled much faster than light."
+        );
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let obj = ObjFile::parse("# a comment\n\n   \nv 1 2 3\n").unwrap();
+
+        assert!(obj.ignored().is_empty());
+        assert_eq!(obj.vertex(1), Some(&Tuple::new_point(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn vertex_records() {
+        let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+        let obj = ObjFile::parse(file).unwrap();
+
+        assert_eq!(obj.vertex(1), Some(&Tuple::new_point(-1.0, 1.0, 0.0)));
+        assert_eq!(obj.vertex(2), Some(&Tuple::new_point(-1.0, 0.5, 0.0)));
+        assert_eq!(obj.vertex(3), Some(&Tuple::new_point(1.0, 0.0, 0.0)));
+        assert_eq!(obj.vertex(4), Some(&Tuple::new_point(1.0, 1.0, 0.0)));
+        assert_eq!(obj.vertex(0), None);
+        assert_eq!(obj.vertex(5), None);
+    }
+
+    #[test]
+    fn parsing_triangle_faces() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+        let obj = ObjFile::parse(file).unwrap();
+        let children = obj.default_group().children();
+        let t1 = as_triangle(children[0].as_ref());
+        let t2 = as_triangle(children[1].as_ref());
+
+        assert_eq!(t1.p1(), obj.vertex(1).unwrap());
+        assert_eq!(t1.p2(), obj.vertex(2).unwrap());
+        assert_eq!(t1.p3(), obj.vertex(3).unwrap());
+        assert_eq!(t2.p1(), obj.vertex(1).unwrap());
+        assert_eq!(t2.p2(), obj.vertex(3).unwrap());
+        assert_eq!(t2.p3(), obj.vertex(4).unwrap());
+    }
+
+    #[test]
+    fn triangulating_polygons() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+        let obj = ObjFile::parse(file).unwrap();
+        let children = obj.default_group().children();
+
+        assert_eq!(children.len(), 3);
+        for (i, child) in children.iter().enumerate() {
+            let t = as_triangle(child.as_ref());
+            assert_eq!(t.p1(), obj.vertex(1).unwrap());
+            assert_eq!(t.p2(), obj.vertex(i + 2).unwrap());
+            assert_eq!(t.p3(), obj.vertex(i + 3).unwrap());
+        }
+    }
+
+    #[test]
+    fn triangles_in_groups() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let obj = ObjFile::parse(file).unwrap();
+        let g1 = obj.group("FirstGroup").unwrap();
+        let g2 = obj.group("SecondGroup").unwrap();
+        let t1 = as_triangle(g1.children()[0].as_ref());
+        let t2 = as_triangle(g2.children()[0].as_ref());
+
+        assert!(obj.default_group().is_empty());
+        assert_eq!(t1.p1(), obj.vertex(1).unwrap());
+        assert_eq!(t1.p2(), obj.vertex(2).unwrap());
+        assert_eq!(t1.p3(), obj.vertex(3).unwrap());
+        assert_eq!(t2.p1(), obj.vertex(1).unwrap());
+        assert_eq!(t2.p2(), obj.vertex(3).unwrap());
+        assert_eq!(t2.p3(), obj.vertex(4).unwrap());
+    }
+
+    #[test]
+    // Reopening a group, or naming an object, adds faces to the same group
+    fn reopening_named_groups() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+o Thing
+f 1 2 3
+g Other
+f 1 2 3
+g Thing
+f 1 2 3";
+        let obj = ObjFile::parse(file).unwrap();
+
+        assert_eq!(obj.group("Thing").unwrap().children().len(), 2);
+        assert_eq!(obj.group("Other").unwrap().children().len(), 1);
+    }
+
+    #[test]
+    fn converting_obj_file_to_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 4
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let group = ObjFile::parse(file).unwrap().into_group();
+
+        // the ungrouped triangle, then both named groups
+        assert_eq!(group.children().len(), 3);
+        let first: &dyn Any = group.children()[1].as_ref();
+        assert_eq!(first.downcast_ref::<Group>().unwrap().children().len(), 1);
+    }
+
+    #[test]
+    fn vertex_normal_records() {
+        let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3";
+        let obj = ObjFile::parse(file).unwrap();
+
+        assert_eq!(obj.normal(1), Some(&Tuple::new_vector(0.0, 0.0, 1.0)));
+        assert_eq!(obj.normal(2), Some(&Tuple::new_vector(0.707, 0.0, -0.707)));
+        assert_eq!(obj.normal(3), Some(&Tuple::new_vector(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn texture_coordinate_records() {
+        let obj = ObjFile::parse("vt 0.5 0.25\nvt 0.75").unwrap();
+
+        assert_eq!(obj.texture_coord(1), Some((0.5, 0.25)));
+        assert_eq!(obj.texture_coord(2), Some((0.75, 0.0)));
+    }
+
+    #[test]
+    fn faces_with_normals() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/1/3 2/1/1 3/1/2";
+        let obj = ObjFile::parse(file).unwrap();
+        let children = obj.default_group().children();
+
+        for child in children {
+            let t = as_smooth_triangle(child.as_ref());
+            assert_eq!(t.p1(), obj.vertex(1).unwrap());
+            assert_eq!(t.p2(), obj.vertex(2).unwrap());
+            assert_eq!(t.p3(), obj.vertex(3).unwrap());
+            assert_eq!(t.n1(), obj.normal(3).unwrap());
+            assert_eq!(t.n2(), obj.normal(1).unwrap());
+            assert_eq!(t.n3(), obj.normal(2).unwrap());
+        }
+    }
+
+    #[test]
+    fn faces_with_texture_coordinates_only_are_flat() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+f 1/1 2/1 3/1";
+        let obj = ObjFile::parse(file).unwrap();
+
+        as_triangle(obj.default_group().children()[0].as_ref());
+    }
+
+    #[test]
+    // Negative indices count back from the last vertex defined so far
+    fn faces_with_negative_indices() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f -3 -2 -1";
+        let obj = ObjFile::parse(file).unwrap();
+        let t = as_triangle(obj.default_group().children()[0].as_ref());
+
+        assert_eq!(t.p1(), obj.vertex(1).unwrap());
+        assert_eq!(t.p3(), obj.vertex(3).unwrap());
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        assert_eq!(
+            ObjFile::parse("v 1 2 3\nv 1 x 3").unwrap_err(),
+            ObjError::InvalidNumber {
+                line: 2,
+                value: "x".to_string()
+            }
+        );
+        assert_eq!(
+            ObjFile::parse("\nvn 1 2").unwrap_err(),
+            ObjError::MissingValues {
+                line: 2,
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(
+            ObjFile::parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4").unwrap_err(),
+            ObjError::InvalidIndex { line: 4, index: 4 }
+        );
+        assert_eq!(
+            ObjFile::parse("v 0 0 0\nv 1 0 0\nf 1 2").unwrap_err(),
+            ObjError::MissingValues {
+                line: 3,
+                expected: 3,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn binding_materials_to_faces() {
+        let library = MtlLibrary::parse("newmtl red\nKd 1 0 0\nnewmtl blue\nKd 0 0 1").unwrap();
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+mtllib colors.mtl
+f 1 2 3
+usemtl red
+f 1 2 3
+usemtl blue
+f 1 2 3
+usemtl missing
+f 1 2 3";
+        let obj = ObjFile::parse_with_materials(file, library).unwrap();
+        let colors: Vec<Color> = obj
+            .default_group()
+            .children()
+            .iter()
+            .map(|child| child.material().color.clone())
+            .collect();
+
+        assert_eq!(
+            colors,
+            [
+                Color::new(1.0, 1.0, 1.0),
+                Color::new(1.0, 0.0, 0.0),
+                Color::new(0.0, 0.0, 1.0),
+                Color::new(1.0, 1.0, 1.0),
+            ]
+        );
+        // both the mtllib and the unknown usemtl
+        let ignored: Vec<usize> = obj.ignored().iter().map(|ignored| ignored.line).collect();
+        assert_eq!(ignored, [4, 10]);
+    }
+
+    #[test]
+    fn loading_files_with_material_libraries() {
+        let directory = std::env::temp_dir().join(format!("raytracer-obj-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("shiny.mtl"),
+            "newmtl shiny\nNs 300\nmap_Kd shiny.png\n",
+        )
+        .unwrap();
+        fs::write(directory.join("broken.mtl"), "Ns 300\n").unwrap();
+        fs::write(
+            directory.join("model.obj"),
+            "mtllib shiny.mtl\nv 0 1 0\nv -1 0 0\nv 1 0 0\nusemtl shiny\nf 1 2 3\n",
+        )
+        .unwrap();
+        fs::write(directory.join("broken.obj"), "mtllib broken.mtl\n").unwrap();
+
+        let obj = ObjFile::load(directory.join("model.obj"));
+        let broken = ObjFile::load(directory.join("broken.obj"));
+        let missing = ObjFile::load(directory.join("missing.obj"));
+        fs::remove_dir_all(&directory).unwrap();
+
+        let obj = obj.unwrap();
+        assert_eq!(obj.materials().len(), 1);
+        assert_eq!(
+            obj.default_group().children()[0].material().shininess,
+            300.0
+        );
+        assert_eq!(
+            obj.ignored(),
+            [IgnoredLine {
+                line: 3,
+                text: "map_Kd shiny.png".to_string(),
+                path: Some(directory.join("shiny.mtl")),
+            }]
+        );
+        assert!(obj.materials().ignored().is_empty());
+        assert_eq!(
+            broken.unwrap_err(),
+            ObjError::InLibrary {
+                path: directory.join("broken.mtl"),
+                error: Box::new(ObjError::MaterialNotDeclared { line: 1 }),
+            }
+        );
+        assert_eq!(
+            missing.unwrap_err(),
+            ObjError::Io {
+                path: directory.join("missing.obj"),
+                kind: io::ErrorKind::NotFound,
+            }
+        );
+    }
+
+    #[test]
+    fn displaying_errors() {
+        let error = ObjError::InvalidIndex { line: 4, index: 0 };
+        assert_eq!(error.to_string(), "line 4: invalid index 0");
+    }
+}
@@ -0,0 +1,326 @@
+use super::{IgnoredLine, ObjError, parse_numbers};
+use crate::{draw::Color, material::Material};
+
+/// A set of named materials, read from a Wavefront MTL file
+#[derive(Debug, Clone, Default)]
+pub struct MtlLibrary {
+    materials: Vec<(String, Material)>,
+    ignored: Vec<IgnoredLine>,
+}
+
+/// The statements of one material, as read from the file. They only become
+/// a `Material` once the whole declaration is known, since the illumination
+/// model changes how the other values are interpreted.
+#[derive(Debug, Default)]
+struct MtlEntry {
+    name: String,
+    ambient: Option<Color>,
+    diffuse: Option<Color>,
+    specular: Option<Color>,
+    shininess: Option<f64>,
+    transparency: Option<f64>,
+    refractive_index: Option<f64>,
+    illum: Option<u32>,
+}
+
+impl MtlLibrary {
+    /// Creates an empty library
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the content of an MTL file. Supports `newmtl`, `Ka`, `Kd`,
+    /// `Ks`, `Ns`, `d`, `Tr`, `Ni` and `illum`; any other statement (such
+    /// as texture maps) is recorded as ignored.
+    ///
+    /// Materials are mapped onto the Phong model as follows:
+    /// - `Kd` gives the surface color
+    /// - `Ka` and `Ks` give the ambient and specular factors, as the mean of
+    ///   their channels
+    /// - `Ns` gives the shininess, and `Ni` the refractive index
+    /// - `d` (dissolve) gives the transparency as `1 - d`, `Tr` directly
+    /// - `illum` 0 renders the flat color, 1 disables highlights, and 3 to
+    ///   8 make the surface reflective (by the specular factor)
+    pub fn parse(input: &str) -> Result<Self, ObjError> {
+        let mut library = Self::new();
+        let mut entry: Option<MtlEntry> = None;
+
+        for (index, text) in input.lines().enumerate() {
+            let line = index + 1;
+            let mut words = text.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let args: Vec<&str> = words.collect();
+
+            if keyword.starts_with('#') {
+                continue;
+            }
+            if keyword == "newmtl" && !args.is_empty() {
+                if let Some(entry) = entry.take() {
+                    library.insert(entry.name.clone(), entry.into_material());
+                }
+                entry = Some(MtlEntry {
+                    name: args.join(" "),
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let recognized = matches!(
+                keyword,
+                "Ka" | "Kd" | "Ks" | "Ns" | "d" | "Tr" | "Ni" | "illum"
+            );
+            if !recognized {
+                library.ignored.push(IgnoredLine {
+                    line,
+                    text: text.to_string(),
+                    path: None,
+                });
+                continue;
+            }
+            let Some(entry) = entry.as_mut() else {
+                return Err(ObjError::MaterialNotDeclared { line });
+            };
+
+            match keyword {
+                "Ka" => entry.ambient = Some(parse_color(line, &args)?),
+                "Kd" => entry.diffuse = Some(parse_color(line, &args)?),
+                "Ks" => entry.specular = Some(parse_color(line, &args)?),
+                "Ns" => entry.shininess = Some(parse_numbers::<1>(line, &args)?[0]),
+                "d" => entry.transparency = Some(1.0 - parse_numbers::<1>(line, &args)?[0]),
+                "Tr" => entry.transparency = Some(parse_numbers::<1>(line, &args)?[0]),
+                "Ni" => entry.refractive_index = Some(parse_numbers::<1>(line, &args)?[0]),
+                _ => entry.illum = Some(parse_illum(line, &args)?),
+            }
+        }
+
+        if let Some(entry) = entry {
+            library.insert(entry.name.clone(), entry.into_material());
+        }
+        Ok(library)
+    }
+
+    /// Returns the material with the given name
+    pub fn get(&self, name: &str) -> Option<&Material> {
+        self.materials
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, material)| material)
+    }
+
+    /// Adds a material to the library, replacing any material with the same
+    /// name
+    pub fn insert(&mut self, name: String, material: Material) {
+        match self.materials.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = material,
+            None => self.materials.push((name, material)),
+        }
+    }
+
+    /// Adds every material of another library to this one, along with its
+    /// ignored lines
+    pub fn extend(&mut self, other: MtlLibrary) {
+        for (name, material) in other.materials {
+            self.insert(name, material);
+        }
+        self.ignored.extend(other.ignored);
+    }
+
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Returns the lines that were skipped as unrecognized
+    pub fn ignored(&self) -> &[IgnoredLine] {
+        &self.ignored
+    }
+
+    /// Removes the lines that were skipped as unrecognized, and returns them
+    pub(super) fn take_ignored(&mut self) -> Vec<IgnoredLine> {
+        std::mem::take(&mut self.ignored)
+    }
+}
+
+impl MtlEntry {
+    fn into_material(self) -> Material {
+        let mut material = Material::new();
+        let mean = |color: &Color| (color.red + color.green + color.blue) / 3.0;
+
+        if let Some(color) = self.diffuse {
+            material.color = color;
+        }
+        if let Some(ambient) = &self.ambient {
+            material.ambient = mean(ambient);
+        }
+        if let Some(specular) = &self.specular {
+            material.specular = mean(specular);
+        }
+        if let Some(shininess) = self.shininess {
+            material.shininess = shininess;
+        }
+        if let Some(transparency) = self.transparency {
+            material.transparency = transparency.clamp(0.0, 1.0);
+        }
+        if let Some(refractive_index) = self.refractive_index {
+            material.refractive_index = refractive_index;
+        }
+
+        match self.illum {
+            // color on and ambient off: the flat color, without any shading
+            Some(0) => {
+                material.ambient = 1.0;
+                material.diffuse = 0.0;
+                material.specular = 0.0;
+            }
+            // color on and ambient on, without highlights
+            Some(1) => material.specular = 0.0,
+            // the various reflection (and refraction) models
+            Some(3..=8) => material.reflective = material.specular,
+            _ => {}
+        }
+
+        material
+    }
+}
+
+/// Parses an illumination model, which is a non-negative integer
+fn parse_illum(line: usize, args: &[&str]) -> Result<u32, ObjError> {
+    let value = args.first().ok_or(ObjError::MissingValues {
+        line,
+        expected: 1,
+        found: 0,
+    })?;
+    value.parse().map_err(|_| ObjError::InvalidNumber {
+        line,
+        value: value.to_string(),
+    })
+}
+
+fn parse_color(line: usize, args: &[&str]) -> Result<Color, ObjError> {
+    // "Ka spectral ..." and "Ka xyz ..." forms are not supported
+    let [red, green, blue] = parse_numbers(line, args)?;
+    Ok(Color::new(red, green, blue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EPSILON;
+
+    #[test]
+    fn parsing_material_library() {
+        let file = "# two materials
+newmtl red
+Ka 0.2 0.2 0.2
+Kd 1.0 0.0 0.0
+Ks 0.5 0.5 0.5
+Ns 50
+
+newmtl glass
+Kd 0.1 0.1 0.1
+d 0.25
+Ni 1.5
+";
+        let library = MtlLibrary::parse(file).unwrap();
+        let red = library.get("red").unwrap();
+        let glass = library.get("glass").unwrap();
+
+        assert_eq!(library.len(), 2);
+        assert_eq!(red.color, Color::new(1.0, 0.0, 0.0));
+        assert!((red.ambient - 0.2).abs() < EPSILON);
+        assert_eq!(red.specular, 0.5);
+        assert_eq!(red.shininess, 50.0);
+        assert_eq!(red.transparency, 0.0);
+        assert_eq!(glass.color, Color::new(0.1, 0.1, 0.1));
+        assert_eq!(glass.transparency, 0.75);
+        assert_eq!(glass.refractive_index, 1.5);
+        assert!(library.get("blue").is_none());
+    }
+
+    #[test]
+    fn missing_statements_keep_default_values() {
+        let library = MtlLibrary::parse("newmtl plain").unwrap();
+        assert_eq!(*library.get("plain").unwrap(), Material::new());
+    }
+
+    #[test]
+    fn illumination_models() {
+        let file = "newmtl flat
+Kd 0 1 0
+illum 0
+newmtl matte
+illum 1
+newmtl shiny
+Ks 0.6 0.6 0.6
+illum 3";
+        let library = MtlLibrary::parse(file).unwrap();
+        let flat = library.get("flat").unwrap();
+        let matte = library.get("matte").unwrap();
+        let shiny = library.get("shiny").unwrap();
+
+        assert_eq!(flat.ambient, 1.0);
+        assert_eq!(flat.diffuse, 0.0);
+        assert_eq!(flat.specular, 0.0);
+        assert_eq!(matte.specular, 0.0);
+        assert_eq!(shiny.reflective, 0.6);
+    }
+
+    #[test]
+    fn illumination_model_is_an_integer() {
+        for value in ["-1", "2.7", "two"] {
+            assert_eq!(
+                MtlLibrary::parse(&format!("newmtl a\nillum {value}")).unwrap_err(),
+                ObjError::InvalidNumber {
+                    line: 2,
+                    value: value.to_string()
+                }
+            );
+        }
+        assert_eq!(
+            MtlLibrary::parse("newmtl a\nillum").unwrap_err(),
+            ObjError::MissingValues {
+                line: 2,
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn ignoring_unsupported_statements() {
+        let file = "newmtl textured
+map_Kd wood.png
+Kd 1 1 1";
+        let library = MtlLibrary::parse(file).unwrap();
+
+        assert_eq!(library.ignored().len(), 1);
+        assert_eq!(library.ignored()[0].line, 2);
+    }
+
+    #[test]
+    fn statement_outside_material_is_an_error() {
+        assert_eq!(
+            MtlLibrary::parse("\nKd 1 0 0").unwrap_err(),
+            ObjError::MaterialNotDeclared { line: 2 }
+        );
+    }
+
+    #[test]
+    fn redeclaring_material_replaces_it() {
+        let mut library = MtlLibrary::parse("newmtl a\nNs 10").unwrap();
+        library.extend(MtlLibrary::parse("newmtl a\nNs 20\nnewmtl b").unwrap());
+
+        assert_eq!(library.len(), 2);
+        assert_eq!(library.get("a").unwrap().shininess, 20.0);
+
+        // ignored lines are carried over too
+        library.extend(MtlLibrary::parse("newmtl c\nmap_Kd c.png").unwrap());
+        assert_eq!(library.ignored().len(), 1);
+        assert_eq!(library.ignored()[0].text, "map_Kd c.png");
+    }
+}
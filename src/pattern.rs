@@ -0,0 +1,174 @@
+mod blend;
+mod checkers;
+mod gradient;
+mod radial_gradient;
+mod ring;
+mod solid;
+mod stripe;
+
+pub use blend::Blend;
+pub use checkers::Checkers;
+pub use gradient::Gradient;
+pub use radial_gradient::RadialGradient;
+pub use ring::Ring;
+pub use solid::Solid;
+pub use stripe::Stripe;
+
+use crate::{draw::Color, matrix::Matrix4, shape::Shape, tuple::Tuple};
+use std::fmt;
+
+/// State shared by every pattern: its pattern-to-object transform and the
+/// cached inverse
+#[derive(Debug, Clone)]
+pub struct PatternData {
+    transform: Matrix4,
+    inverse: Matrix4,
+}
+
+impl PatternData {
+    /// Creates pattern state with an identity transform
+    pub fn new() -> Self {
+        Self {
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+        }
+    }
+}
+
+impl Default for PatternData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A procedural texture mapping points in space to colors. Implementors
+/// only describe the pattern in its own pattern space through
+/// `local_pattern_at`; the provided methods take care of the object and
+/// pattern transforms.
+pub trait Pattern: fmt::Debug + Send + Sync {
+    /// Returns the state shared by every pattern
+    fn data(&self) -> &PatternData;
+
+    /// Returns the state shared by every pattern, mutably
+    fn data_mut(&mut self) -> &mut PatternData;
+
+    /// Computes the color of the pattern at a point in pattern space
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color;
+
+    /// Returns the transformation matrix of the pattern, relative to the
+    /// object (or enclosing pattern) it is applied to
+    fn transform(&self) -> &Matrix4 {
+        &self.data().transform
+    }
+
+    /// Returns the inverse of the pattern's transformation matrix
+    fn inverse(&self) -> &Matrix4 {
+        &self.data().inverse
+    }
+
+    /// Sets the transformation matrix of the pattern
+    /// ## Panics
+    /// Will panic if the matrix is not invertible.
+    fn set_transform(&mut self, transform: Matrix4) {
+        let data = self.data_mut();
+        data.inverse = transform
+            .clone()
+            .inverse()
+            .expect("Pattern transform should be invertible");
+        data.transform = transform;
+    }
+
+    /// Computes the color of the pattern at a point given in the space the
+    /// pattern is applied in, which is object space for a pattern on a
+    /// shape, or the parent's pattern space for a nested pattern
+    fn pattern_at(&self, point: &Tuple) -> Color {
+        self.local_pattern_at(&(self.inverse() * point))
+    }
+
+    /// Computes the color of the pattern at a point in world space on the
+    /// given shape
+    fn pattern_at_shape(&self, object: &dyn Shape, world_point: &Tuple) -> Color {
+        let object_point = object.world_to_object(world_point);
+        self.pattern_at(&object_point)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{shape::Sphere, transform};
+
+    /// A pattern returning the pattern space point it was evaluated at as
+    /// a color
+    #[derive(Debug, Default)]
+    pub(crate) struct TestPattern {
+        data: PatternData,
+    }
+
+    impl TestPattern {
+        pub(crate) fn new() -> Self {
+            Self {
+                data: PatternData::new(),
+            }
+        }
+    }
+
+    impl Pattern for TestPattern {
+        fn data(&self) -> &PatternData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut PatternData {
+            &mut self.data
+        }
+
+        fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+            let (x, y, z, _) = pattern_point.get();
+            Color::new(x, y, z)
+        }
+    }
+
+    #[test]
+    fn default_pattern_transformation() {
+        let pattern = TestPattern::new();
+        assert_eq!(*pattern.transform(), Matrix4::identity());
+    }
+
+    #[test]
+    fn assigning_a_transformation() {
+        let mut pattern = TestPattern::new();
+        pattern.set_transform(transform::translation(1.0, 2.0, 3.0));
+        assert_eq!(*pattern.transform(), transform::translation(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn pattern_with_object_transformation() {
+        let mut shape = Sphere::new();
+        shape.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let pattern = TestPattern::new();
+        let color = pattern.pattern_at_shape(&shape, &Tuple::new_point(2.0, 3.0, 4.0));
+
+        assert_eq!(color, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_pattern_transformation() {
+        let shape = Sphere::new();
+        let mut pattern = TestPattern::new();
+        pattern.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let color = pattern.pattern_at_shape(&shape, &Tuple::new_point(2.0, 3.0, 4.0));
+
+        assert_eq!(color, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_object_and_pattern_transformation() {
+        let mut shape = Sphere::new();
+        shape.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let mut pattern = TestPattern::new();
+        pattern.set_transform(transform::translation(0.5, 1.0, 1.5));
+        let color = pattern.pattern_at_shape(&shape, &Tuple::new_point(2.5, 3.0, 3.5));
+
+        assert_eq!(color, Color::new(0.75, 0.5, 0.25));
+    }
+}
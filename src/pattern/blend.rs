@@ -0,0 +1,68 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData},
+    tuple::Tuple,
+};
+
+/// A pattern averaging the colors of two sub-patterns at every point, for
+/// instance two perpendicular stripe patterns to create a plaid
+#[derive(Debug)]
+pub struct Blend {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+}
+
+impl Blend {
+    pub fn new(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::new(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Blend {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+        (self.a.pattern_at(pattern_point) + self.b.pattern_at(pattern_point)) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pattern::Stripe, transform};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn blend_averages_sub_patterns() {
+        let white = Color::new(1.0, 1.0, 1.0);
+        let black = Color::new(0.0, 0.0, 0.0);
+        let horizontal = Stripe::new(white.clone(), black.clone());
+        let mut vertical = Stripe::new(white, black);
+        vertical.set_transform(transform::rotation_y(PI / 2.0));
+        let pattern = Blend::new(Box::new(horizontal), Box::new(vertical));
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.5, 0.0, -0.5)),
+            Color::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.5, 0.0, -0.5)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.5, 0.0, 0.5)),
+            Color::new(0.0, 0.0, 0.0)
+        );
+    }
+}
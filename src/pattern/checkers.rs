@@ -0,0 +1,133 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData, Solid},
+    tuple::Tuple,
+};
+
+/// A 3D checkerboard of unit cubes alternating between two sub-patterns
+#[derive(Debug)]
+pub struct Checkers {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+}
+
+impl Checkers {
+    /// Creates checkers alternating between two flat colors
+    pub fn new(a: Color, b: Color) -> Self {
+        Self::with_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    /// Creates checkers alternating between two (nested) patterns
+    pub fn with_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::new(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Checkers {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+        let (x, y, z, _) = pattern_point.get();
+        let sum = x.floor() as i64 + y.floor() as i64 + z.floor() as i64;
+        if sum.rem_euclid(2) == 0 {
+            self.a.pattern_at(pattern_point)
+        } else {
+            self.b.pattern_at(pattern_point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    #[test]
+    // Checkers should repeat in x
+    fn checkers_repeat_in_x() {
+        let pattern = Checkers::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.99, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.01, 0.0, 0.0)),
+            black()
+        );
+    }
+
+    #[test]
+    // Checkers should repeat in y
+    fn checkers_repeat_in_y() {
+        let pattern = Checkers::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.99, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 1.01, 0.0)),
+            black()
+        );
+    }
+
+    #[test]
+    // Checkers should repeat in z
+    fn checkers_repeat_in_z() {
+        let pattern = Checkers::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.99)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 1.01)),
+            black()
+        );
+    }
+
+    #[test]
+    fn checkers_alternate_across_negative_coordinates() {
+        let pattern = Checkers::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-0.5, 0.0, 0.0)),
+            black()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-0.5, -0.5, 0.0)),
+            white()
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData, Solid},
+    tuple::Tuple,
+};
+
+/// A pattern linearly interpolating from one sub-pattern to another along
+/// the x axis, repeating every unit
+#[derive(Debug)]
+pub struct Gradient {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+}
+
+impl Gradient {
+    /// Creates a gradient between two flat colors
+    pub fn new(a: Color, b: Color) -> Self {
+        Self::with_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    /// Creates a gradient between two (nested) patterns
+    pub fn with_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::new(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Gradient {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+        let (x, _, _, _) = pattern_point.get();
+        let a = self.a.pattern_at(pattern_point);
+        let b = self.b.pattern_at(pattern_point);
+        let distance = &b - &a;
+        a + distance * (x - x.floor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A gradient linearly interpolates between colors
+    fn gradient_interpolates_between_colors() {
+        let pattern = Gradient::new(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            Color::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.25, 0.0, 0.0)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.5, 0.0, 0.0)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.75, 0.0, 0.0)),
+            Color::new(0.25, 0.25, 0.25)
+        );
+    }
+
+    #[test]
+    fn gradient_repeats_every_unit() {
+        let pattern = Gradient::new(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.25, 0.0, 0.0)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-0.75, 0.0, 0.0)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+    }
+}
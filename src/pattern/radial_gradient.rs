@@ -0,0 +1,76 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData, Solid},
+    tuple::Tuple,
+};
+
+/// A pattern interpolating from one sub-pattern to another with the
+/// distance from the y axis, repeating every unit like concentric rings
+#[derive(Debug)]
+pub struct RadialGradient {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+}
+
+impl RadialGradient {
+    /// Creates a radial gradient between two flat colors
+    pub fn new(a: Color, b: Color) -> Self {
+        Self::with_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    /// Creates a radial gradient between two (nested) patterns
+    pub fn with_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::new(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for RadialGradient {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+        let (x, _, z, _) = pattern_point.get();
+        let radius = (x * x + z * z).sqrt();
+        let a = self.a.pattern_at(pattern_point);
+        let b = self.b.pattern_at(pattern_point);
+        let distance = &b - &a;
+        a + distance * (radius - radius.floor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radial_gradient_interpolates_with_distance_from_y_axis() {
+        let pattern = RadialGradient::new(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            Color::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.25, 0.0, 0.0)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 5.0, 0.5)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.25, 0.0, 0.0)),
+            Color::new(0.75, 0.75, 0.75)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData, Solid},
+    tuple::Tuple,
+};
+
+/// A pattern of concentric rings around the y axis, alternating between
+/// two sub-patterns every unit of distance in the xz plane
+#[derive(Debug)]
+pub struct Ring {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+}
+
+impl Ring {
+    /// Creates rings alternating between two flat colors
+    pub fn new(a: Color, b: Color) -> Self {
+        Self::with_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    /// Creates rings alternating between two (nested) patterns
+    pub fn with_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::new(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Ring {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+        let (x, _, z, _) = pattern_point.get();
+        let distance = (x * x + z * z).sqrt();
+        if (distance.floor() as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at(pattern_point)
+        } else {
+            self.b.pattern_at(pattern_point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A ring should extend in both x and z
+    fn ring_extends_in_x_and_z() {
+        let white = Color::new(1.0, 1.0, 1.0);
+        let black = Color::new(0.0, 0.0, 0.0);
+        let pattern = Ring::new(white.clone(), black.clone());
+
+        assert_eq!(pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)), white);
+        assert_eq!(pattern.pattern_at(&Tuple::new_point(1.0, 0.0, 0.0)), black);
+        assert_eq!(pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 1.0)), black);
+        // 0.708 = just slightly more than √2/2
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.708, 0.0, 0.708)),
+            black
+        );
+    }
+}
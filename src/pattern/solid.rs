@@ -0,0 +1,55 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData},
+    tuple::Tuple,
+};
+
+/// A pattern of a single flat color, mostly useful as a building block for
+/// nested patterns
+#[derive(Debug, Clone)]
+pub struct Solid {
+    data: PatternData,
+    color: Color,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Self {
+        Self {
+            data: PatternData::new(),
+            color,
+        }
+    }
+}
+
+impl Pattern for Solid {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, _pattern_point: &Tuple) -> Color {
+        self.color.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solid_pattern_is_constant() {
+        let pattern = Solid::new(Color::new(0.2, 0.4, 0.6));
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            Color::new(0.2, 0.4, 0.6)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-3.5, 12.0, 7.25)),
+            Color::new(0.2, 0.4, 0.6)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    draw::Color,
+    pattern::{Pattern, PatternData, Solid},
+    tuple::Tuple,
+};
+
+/// A pattern alternating between two sub-patterns every unit along the x
+/// axis
+#[derive(Debug)]
+pub struct Stripe {
+    data: PatternData,
+    a: Box<dyn Pattern>,
+    b: Box<dyn Pattern>,
+}
+
+impl Stripe {
+    /// Creates stripes alternating between two flat colors
+    pub fn new(a: Color, b: Color) -> Self {
+        Self::with_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    /// Creates stripes alternating between two (nested) patterns
+    pub fn with_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::new(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Stripe {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn local_pattern_at(&self, pattern_point: &Tuple) -> Color {
+        let (x, _, _, _) = pattern_point.get();
+        if (x.floor() as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at(pattern_point)
+        } else {
+            self.b.pattern_at(pattern_point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pattern::tests::TestPattern, shape::Shape, shape::Sphere, transform};
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    #[test]
+    // A stripe pattern is constant in y
+    fn stripe_is_constant_in_y() {
+        let pattern = Stripe::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 1.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 2.0, 0.0)),
+            white()
+        );
+    }
+
+    #[test]
+    // A stripe pattern is constant in z
+    fn stripe_is_constant_in_z() {
+        let pattern = Stripe::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 1.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 2.0)),
+            white()
+        );
+    }
+
+    #[test]
+    // A stripe pattern alternates in x
+    fn stripe_alternates_in_x() {
+        let pattern = Stripe::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.0, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.9, 0.0, 0.0)),
+            white()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.0, 0.0, 0.0)),
+            black()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-0.1, 0.0, 0.0)),
+            black()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-1.0, 0.0, 0.0)),
+            black()
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(-1.1, 0.0, 0.0)),
+            white()
+        );
+    }
+
+    #[test]
+    fn stripes_with_object_transformation() {
+        let mut object = Sphere::new();
+        object.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let pattern = Stripe::new(white(), black());
+
+        assert_eq!(
+            pattern.pattern_at_shape(&object, &Tuple::new_point(1.5, 0.0, 0.0)),
+            white()
+        );
+    }
+
+    #[test]
+    fn stripes_with_pattern_transformation() {
+        let object = Sphere::new();
+        let mut pattern = Stripe::new(white(), black());
+        pattern.set_transform(transform::scaling(2.0, 2.0, 2.0));
+
+        assert_eq!(
+            pattern.pattern_at_shape(&object, &Tuple::new_point(1.5, 0.0, 0.0)),
+            white()
+        );
+    }
+
+    #[test]
+    fn stripes_with_object_and_pattern_transformation() {
+        let mut object = Sphere::new();
+        object.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let mut pattern = Stripe::new(white(), black());
+        pattern.set_transform(transform::translation(0.5, 0.0, 0.0));
+
+        assert_eq!(
+            pattern.pattern_at_shape(&object, &Tuple::new_point(2.5, 0.0, 0.0)),
+            white()
+        );
+    }
+
+    #[test]
+    // Sub-patterns are evaluated in the space of the enclosing pattern,
+    // through their own transform
+    fn nested_patterns_apply_their_own_transform() {
+        let mut inner = TestPattern::new();
+        inner.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let pattern = Stripe::with_patterns(Box::new(inner), Box::new(Solid::new(black())));
+
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(0.5, 1.0, 2.0)),
+            Color::new(0.25, 0.5, 1.0)
+        );
+        assert_eq!(
+            pattern.pattern_at(&Tuple::new_point(1.5, 1.0, 2.0)),
+            black()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{matrix::Matrix4, tuple::Tuple};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ray {
+    pub origin: Tuple,
+    pub direction: Tuple,
+}
+
+impl Ray {
+    /// Creates a new ray starting at `origin` (a point) and travelling
+    /// along `direction` (a vector)
+    /// ## Panics
+    /// Will panic if origin is not a point or direction is not a vector.
+    pub fn new(origin: Tuple, direction: Tuple) -> Self {
+        assert!(origin.is_point(), "{origin} is not a point.");
+        assert!(direction.is_vector(), "{direction} is not a vector.");
+        Self { origin, direction }
+    }
+
+    /// Computes the point at distance t along the ray
+    /// ```text
+    /// position(t) = origin + direction * t
+    /// ```
+    pub fn position(&self, t: f64) -> Tuple {
+        &self.origin + &self.direction * t
+    }
+
+    /// Returns a new ray whose origin and direction are multiplied by the
+    /// given transformation matrix
+    pub fn transform(&self, matrix: &Matrix4) -> Ray {
+        Ray {
+            origin: matrix * &self.origin,
+            direction: matrix * &self.direction,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform;
+
+    #[test]
+    fn creating_and_querying_a_ray() {
+        let origin = Tuple::new_point(1.0, 2.0, 3.0);
+        let direction = Tuple::new_vector(4.0, 5.0, 6.0);
+        let ray = Ray::new(origin.clone(), direction.clone());
+
+        assert_eq!(ray.origin, origin);
+        assert_eq!(ray.direction, direction);
+    }
+
+    #[test]
+    fn computing_a_point_from_a_distance() {
+        let ray = Ray::new(
+            Tuple::new_point(2.0, 3.0, 4.0),
+            Tuple::new_vector(1.0, 0.0, 0.0),
+        );
+
+        assert_eq!(ray.position(0.0), Tuple::new_point(2.0, 3.0, 4.0));
+        assert_eq!(ray.position(1.0), Tuple::new_point(3.0, 3.0, 4.0));
+        assert_eq!(ray.position(-1.0), Tuple::new_point(1.0, 3.0, 4.0));
+        assert_eq!(ray.position(2.5), Tuple::new_point(4.5, 3.0, 4.0));
+    }
+
+    #[test]
+    fn translating_a_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(1.0, 2.0, 3.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let matrix = transform::translation(3.0, 4.0, 5.0);
+        let ray2 = ray.transform(&matrix);
+
+        assert_eq!(ray2.origin, Tuple::new_point(4.0, 6.0, 8.0));
+        assert_eq!(ray2.direction, Tuple::new_vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn scaling_a_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(1.0, 2.0, 3.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let matrix = transform::scaling(2.0, 3.0, 4.0);
+        let ray2 = ray.transform(&matrix);
+
+        assert_eq!(ray2.origin, Tuple::new_point(2.0, 6.0, 12.0));
+        assert_eq!(ray2.direction, Tuple::new_vector(0.0, 3.0, 0.0));
+    }
+}
@@ -0,0 +1,529 @@
+use crate::{
+    camera::Camera,
+    draw::{Canvas, Color},
+    world::World,
+};
+use std::{
+    fmt,
+    num::NonZero,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+mod adaptive;
+mod sampling;
+
+pub use adaptive::Adaptive;
+pub use sampling::{Filter, SamplePattern, Supersampling};
+
+/// Default width and height of the square tiles the image is split into
+pub const DEFAULT_TILE_SIZE: usize = 16;
+
+/// Settings controlling how a camera renders a world
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Number of worker threads rendering tiles. Zero is treated as one.
+    pub threads: usize,
+    /// Width and height of the tiles, in pixels. Zero is treated as one.
+    pub tile_size: usize,
+    pub antialiasing: Antialiasing,
+}
+
+/// How many rays are traced for each pixel
+#[derive(Debug, Clone, PartialEq)]
+pub enum Antialiasing {
+    /// A single ray through the center of each pixel
+    None,
+    /// A fixed number of rays per pixel
+    Supersampling(Supersampling),
+    /// More rays where the image has the most contrast
+    Adaptive(Adaptive),
+}
+
+impl Antialiasing {
+    /// Renders the pixels of a tile, in row major order, along with the
+    /// number of rays traced for each of them
+    fn render_tile(&self, camera: &Camera, world: &World, tile: &Tile) -> Vec<(Color, usize)> {
+        if let Antialiasing::Adaptive(adaptive) = self {
+            return adaptive.render_tile(camera, world, tile);
+        }
+        let mut pixels = Vec::with_capacity(tile.width * tile.height);
+
+        for y in tile.y..tile.y + tile.height {
+            for x in tile.x..tile.x + tile.width {
+                pixels.push(match self {
+                    Antialiasing::Supersampling(supersampling) => (
+                        supersampling.color_at(camera, world, x, y),
+                        supersampling.rays_per_pixel(),
+                    ),
+                    _ => (world.color_at(&camera.ray_for_pixel(x, y)), 1),
+                });
+            }
+        }
+        pixels
+    }
+}
+
+impl RenderOptions {
+    /// Creates options using one worker per available CPU
+    pub fn new() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, NonZero::get),
+            tile_size: DEFAULT_TILE_SIZE,
+            antialiasing: Antialiasing::None,
+        }
+    }
+
+    /// Returns these options, with the given number of worker threads
+    pub fn with_threads(self, threads: usize) -> Self {
+        Self { threads, ..self }
+    }
+
+    /// Returns these options, with the given tile size
+    pub fn with_tile_size(self, tile_size: usize) -> Self {
+        Self { tile_size, ..self }
+    }
+
+    /// Returns these options, with the given anti-aliasing
+    pub fn with_antialiasing(self, antialiasing: Antialiasing) -> Self {
+        Self {
+            antialiasing,
+            ..self
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A rectangular region of the image, rendered as a single unit of work
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tile {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Splits a `width` x `height` image into tiles of at most `size` pixels
+/// on each side, in row major order
+fn tiles(width: usize, height: usize, size: usize) -> Vec<Tile> {
+    let size = size.max(1);
+    let mut tiles = vec![];
+
+    for y in (0..height).step_by(size) {
+        for x in (0..width).step_by(size) {
+            tiles.push(Tile {
+                x,
+                y,
+                width: size.min(width - x),
+                height: size.min(height - y),
+            });
+        }
+    }
+    tiles
+}
+
+/// Progress of a render, reported each time a tile completes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub tiles_done: usize,
+    pub tiles_total: usize,
+    pub pixels_done: usize,
+    pub pixels_total: usize,
+    /// Time spent since the render started
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Returns the completed fraction of the image, between 0 and 1
+    pub fn fraction(&self) -> f64 {
+        if self.pixels_total == 0 {
+            return 1.0;
+        }
+        self.pixels_done as f64 / self.pixels_total as f64
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.tiles_done == self.tiles_total
+    }
+
+    /// Estimates the time left to finish the render, assuming the remaining
+    /// pixels take as long as the ones done so far. Returns None until the
+    /// first tile is done.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.pixels_done == 0 {
+            return None;
+        }
+        let remaining = (self.pixels_total - self.pixels_done) as f64;
+        Some(self.elapsed.mul_f64(remaining / self.pixels_done as f64))
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} tiles ({:.1}%), {:.1?} elapsed",
+            self.tiles_done,
+            self.tiles_total,
+            self.fraction() * 100.0,
+            self.elapsed
+        )?;
+        match self.eta() {
+            Some(eta) => write!(f, ", ETA {eta:.1?}"),
+            None => write!(f, ", ETA unknown"),
+        }
+    }
+}
+
+/// The outcome of a progressive render
+#[derive(Debug)]
+pub struct RenderOutput {
+    /// The rendered image. If the render was cancelled, the pixels of the
+    /// tiles that did not complete are left black.
+    pub canvas: Canvas,
+    /// Progress at the time the render stopped
+    pub progress: Progress,
+    /// Number of rays traced for each pixel, in row major order. Pixels
+    /// that were not rendered count zero rays, and rays shared between
+    /// neighboring pixels are split among them.
+    pub samples: Vec<usize>,
+}
+
+impl RenderOutput {
+    /// Returns true if every tile was rendered, false if the render was
+    /// cancelled before the end
+    pub fn is_complete(&self) -> bool {
+        self.progress.is_complete()
+    }
+
+    /// Returns the number of rays traced for the pixel at (x, y)
+    pub fn samples_at(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.canvas.width() {
+            return None;
+        }
+        self.samples.get(y * self.canvas.width() + x).copied()
+    }
+
+    /// Returns a grayscale image of the number of rays traced for each
+    /// pixel, from black for the fewest to white for the most. Useful to
+    /// see where adaptive anti-aliasing spent its effort.
+    pub fn sample_canvas(&self) -> Canvas {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let min = self.samples.iter().copied().min().unwrap_or(0);
+        let max = self.samples.iter().copied().max().unwrap_or(0);
+        let mut canvas = Canvas::new(width, height);
+
+        for (i, &count) in self.samples.iter().enumerate() {
+            let level = if max > min {
+                (count - min) as f64 / (max - min) as f64
+            } else {
+                0.0
+            };
+            canvas
+                .set_pixel_at(i % width, i / width, &Color::new(level, level, level))
+                .expect("Sample counts should match the canvas");
+        }
+        canvas
+    }
+}
+
+/// Renders the world through the camera, on a pool of worker threads.
+pub(crate) fn render(camera: &Camera, world: &World, options: &RenderOptions) -> Canvas {
+    render_progressive(camera, world, options, &AtomicBool::new(false), |_| {}).canvas
+}
+
+/// Renders the world through the camera, on a pool of worker threads,
+/// calling `on_progress` on the calling thread after each completed tile.
+/// Workers stop picking up new tiles as soon as `cancel` is set; tiles
+/// already in progress are finished and kept.
+///
+/// Workers pick the next tile to render from a shared counter and send the
+/// finished pixels back to the calling thread, which alone writes into the
+/// canvas. Every pixel only depends on its own ray, so the image is the same
+/// whatever the number of threads or the order tiles complete in.
+pub(crate) fn render_progressive<F>(
+    camera: &Camera,
+    world: &World,
+    options: &RenderOptions,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> RenderOutput
+where
+    F: FnMut(&Progress),
+{
+    let start = Instant::now();
+    let tiles = tiles(camera.hsize(), camera.vsize(), options.tile_size);
+    let workers = options.threads.clamp(1, tiles.len().max(1));
+    let next = AtomicUsize::new(0);
+    let mut canvas = Canvas::new(camera.hsize(), camera.vsize());
+    let mut samples = vec![0; canvas.size()];
+    let mut progress = Progress {
+        tiles_done: 0,
+        tiles_total: tiles.len(),
+        pixels_done: 0,
+        pixels_total: canvas.size(),
+        elapsed: Duration::ZERO,
+    };
+
+    thread::scope(|scope| {
+        // a bounded channel keeps workers from running far ahead of the
+        // calling thread, so that cancelling stops the render promptly
+        let (sender, receiver) = mpsc::sync_channel(workers);
+
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (tiles, next) = (&tiles, &next);
+            scope.spawn(move || {
+                while !cancel.load(Ordering::Relaxed) {
+                    let Some(tile) = tiles.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    let pixels = options.antialiasing.render_tile(camera, world, tile);
+                    if sender.send((*tile, pixels)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // the loop below ends once every worker has dropped its sender
+        drop(sender);
+
+        for (tile, pixels) in receiver {
+            write_tile(&mut canvas, &mut samples, &tile, &pixels);
+            progress.tiles_done += 1;
+            progress.pixels_done += pixels.len();
+            progress.elapsed = start.elapsed();
+            on_progress(&progress);
+        }
+    });
+
+    progress.elapsed = start.elapsed();
+    RenderOutput {
+        canvas,
+        progress,
+        samples,
+    }
+}
+
+fn write_tile(canvas: &mut Canvas, samples: &mut [usize], tile: &Tile, pixels: &[(Color, usize)]) {
+    for (i, (color, count)) in pixels.iter().enumerate() {
+        let (x, y) = (tile.x + i % tile.width, tile.y + i / tile.width);
+        canvas
+            .set_pixel_at(x, y, color)
+            .expect("Tile should be within the canvas");
+        samples[y * canvas.width() + x] = *count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transform, tuple::Tuple, world};
+    use std::f64::consts::PI;
+
+    pub(super) fn default_camera(hsize: usize, vsize: usize) -> Camera {
+        let mut camera = Camera::new(hsize, vsize, PI / 2.0);
+        camera.set_transform(transform::view_transform(
+            &Tuple::new_point(0.0, 0.0, -5.0),
+            &Tuple::new_point(0.0, 0.0, 0.0),
+            &Tuple::new_vector(0.0, 1.0, 0.0),
+        ));
+        camera
+    }
+
+    fn assert_same_image(a: &Canvas, b: &Canvas) {
+        assert_eq!(a.size(), b.size());
+        for y in 0..a.height() {
+            for x in 0..a.width() {
+                assert_eq!(a.get_color_at(x, y), b.get_color_at(x, y), "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn splitting_image_into_tiles() {
+        let tiles = tiles(40, 20, 16);
+
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(
+            tiles[0],
+            Tile {
+                x: 0,
+                y: 0,
+                width: 16,
+                height: 16
+            }
+        );
+        // the tiles on the right and bottom edges are cut to fit the image
+        assert_eq!(
+            tiles[5],
+            Tile {
+                x: 32,
+                y: 16,
+                width: 8,
+                height: 4
+            }
+        );
+        let area: usize = tiles.iter().map(|tile| tile.width * tile.height).sum();
+        assert_eq!(area, 40 * 20);
+    }
+
+    #[test]
+    fn empty_image_has_no_tiles() {
+        assert!(tiles(0, 10, 16).is_empty());
+        assert_eq!(tiles(3, 3, 0).len(), 9);
+    }
+
+    #[test]
+    fn rendering_is_independent_of_thread_count() {
+        let world = world::default_world();
+        let camera = default_camera(37, 23);
+        let options = RenderOptions::new().with_tile_size(8);
+
+        let single = render(&camera, &world, &options.clone().with_threads(1));
+        for threads in [0, 2, 3, 8, 64] {
+            let image = render(&camera, &world, &options.clone().with_threads(threads));
+            assert_same_image(&single, &image);
+        }
+    }
+
+    #[test]
+    fn rendering_matches_single_pixel_rays() {
+        let world = world::default_world();
+        let camera = default_camera(11, 11);
+        let image = render(&camera, &world, &RenderOptions::new().with_tile_size(4));
+
+        for y in 0..11 {
+            for x in 0..11 {
+                let color = world.color_at(&camera.ray_for_pixel(x, y));
+                assert_eq!(*image.get_color_at(x, y).unwrap(), color);
+            }
+        }
+    }
+
+    #[test]
+    fn progress_is_reported_for_every_tile() {
+        let world = world::default_world();
+        let camera = default_camera(20, 10);
+        let options = RenderOptions::new().with_tile_size(8).with_threads(3);
+        let mut reports = vec![];
+
+        let output = render_progressive(&camera, &world, &options, &AtomicBool::new(false), |p| {
+            reports.push(p.clone())
+        });
+
+        assert_eq!(reports.len(), 6);
+        for (i, report) in reports.iter().enumerate() {
+            assert_eq!(report.tiles_done, i + 1);
+            assert_eq!(report.tiles_total, 6);
+            assert_eq!(report.pixels_total, 200);
+        }
+        assert_eq!(reports[5].pixels_done, 200);
+        assert!(output.is_complete());
+        assert_same_image(&output.canvas, &render(&camera, &world, &options));
+    }
+
+    #[test]
+    fn cancelling_before_start_renders_nothing() {
+        let world = world::default_world();
+        let camera = default_camera(20, 10);
+        let output = render_progressive(
+            &camera,
+            &world,
+            &RenderOptions::new(),
+            &AtomicBool::new(true),
+            |_| panic!("No tile should be rendered"),
+        );
+
+        assert!(!output.is_complete());
+        assert_eq!(output.progress.tiles_done, 0);
+        assert_eq!(output.canvas.size(), 200);
+        assert_eq!(
+            *output.canvas.get_color_at(10, 5).unwrap(),
+            Color::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn cancelling_keeps_completed_tiles() {
+        let world = world::default_world();
+        let camera = default_camera(32, 32);
+        let options = RenderOptions::new().with_tile_size(4).with_threads(2);
+        let cancel = AtomicBool::new(false);
+
+        let output = render_progressive(&camera, &world, &options, &cancel, |_| {
+            cancel.store(true, Ordering::Relaxed)
+        });
+        let full = render(&camera, &world, &options);
+
+        // tiles already in progress or queued when the flag was set still
+        // complete: at most one per worker, plus the channel capacity
+        assert!(output.progress.tiles_done >= 1);
+        assert!(output.progress.tiles_done <= 5);
+        assert!(!output.is_complete());
+        for y in 0..32 {
+            for x in 0..32 {
+                let color = output.canvas.get_color_at(x, y).unwrap();
+                if *color != Color::new(0.0, 0.0, 0.0) {
+                    assert_eq!(color, full.get_color_at(x, y).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn estimating_remaining_time() {
+        let mut progress = Progress {
+            tiles_done: 0,
+            tiles_total: 4,
+            pixels_done: 0,
+            pixels_total: 400,
+            elapsed: Duration::from_secs(0),
+        };
+        assert_eq!(progress.eta(), None);
+        assert_eq!(
+            progress.to_string(),
+            "0/4 tiles (0.0%), 0.0ns elapsed, ETA unknown"
+        );
+
+        progress.tiles_done = 1;
+        progress.pixels_done = 100;
+        progress.elapsed = Duration::from_secs(2);
+        assert_eq!(progress.fraction(), 0.25);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(6)));
+        assert_eq!(
+            progress.to_string(),
+            "1/4 tiles (25.0%), 2.0s elapsed, ETA 6.0s"
+        );
+    }
+
+    #[test]
+    fn supersampling_is_independent_of_thread_count() {
+        let world = world::default_world();
+        let camera = default_camera(19, 13);
+        let supersampling = Supersampling::new(SamplePattern::Jittered, 4, Filter::Mitchell);
+        let options = RenderOptions::new()
+            .with_tile_size(5)
+            .with_antialiasing(Antialiasing::Supersampling(supersampling));
+
+        let single = render(&camera, &world, &options.clone().with_threads(1));
+        let multi = render(&camera, &world, &options.with_threads(4));
+        assert_same_image(&single, &multi);
+    }
+
+    #[test]
+    fn rendering_empty_image() {
+        let world = world::default_world();
+        let image = render(&default_camera(0, 0), &world, &RenderOptions::new());
+        assert_eq!(image.size(), 0);
+    }
+}
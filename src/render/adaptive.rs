@@ -0,0 +1,259 @@
+use super::Tile;
+use crate::{camera::Camera, draw::Color, world::World};
+
+/// Adaptive anti-aliasing: each pixel is first sampled at its corners and
+/// center, and only subdivided into quarters where those samples differ by
+/// more than the threshold. Corners are shared between neighboring pixels,
+/// so flat areas cost about two rays per pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adaptive {
+    /// Largest difference allowed between the red, green or blue components
+    /// of a corner and the center of a square before it is subdivided
+    pub threshold: f64,
+    /// Number of times a pixel may be subdivided. At depth zero, pixels are
+    /// never subdivided.
+    pub max_depth: usize,
+}
+
+impl Adaptive {
+    pub fn new(threshold: f64, max_depth: usize) -> Self {
+        Self {
+            threshold,
+            max_depth,
+        }
+    }
+
+    /// Renders the pixels of a tile, in row major order, along with the
+    /// number of rays traced for each of them. Corners are shared by four
+    /// pixels, so each pixel counts a quarter of each of its corners.
+    pub(super) fn render_tile(
+        &self,
+        camera: &Camera,
+        world: &World,
+        tile: &Tile,
+    ) -> Vec<(Color, usize)> {
+        let trace = |x: f64, y: f64| world.color_at(&camera.ray_for_point(x, y));
+
+        // the corners of every pixel of the tile, in row major order
+        let columns = tile.width + 1;
+        let mut corners = Vec::with_capacity(columns * (tile.height + 1));
+        for y in tile.y..=tile.y + tile.height {
+            for x in tile.x..=tile.x + tile.width {
+                corners.push(trace(x as f64, y as f64));
+            }
+        }
+
+        let mut pixels = Vec::with_capacity(tile.width * tile.height);
+        for j in 0..tile.height {
+            for i in 0..tile.width {
+                let top = j * columns + i;
+                let bottom = top + columns;
+                let square = [
+                    corners[top].clone(),
+                    corners[top + 1].clone(),
+                    corners[bottom].clone(),
+                    corners[bottom + 1].clone(),
+                ];
+                let origin = ((tile.x + i) as f64, (tile.y + j) as f64);
+                let mut samples = 1;
+                let color = self.sample_square(&trace, origin, 1.0, square, 0, &mut samples);
+                pixels.push((color, samples));
+            }
+        }
+        pixels
+    }
+
+    /// Computes the color of the square of the given size with its top left
+    /// corner at `origin`, from the colors at its corners (top left, top
+    /// right, bottom left, bottom right). Counts the new points sampled in
+    /// `samples`.
+    fn sample_square<F>(
+        &self,
+        trace: &F,
+        (x, y): (f64, f64),
+        size: f64,
+        corners: [Color; 4],
+        depth: usize,
+        samples: &mut usize,
+    ) -> Color
+    where
+        F: Fn(f64, f64) -> Color,
+    {
+        let half = size / 2.0;
+        let center = trace(x + half, y + half);
+        *samples += 1;
+
+        let flat = corners
+            .iter()
+            .all(|corner| !self.contrasts(corner, &center));
+        if flat || depth >= self.max_depth {
+            return corners.into_iter().fold(center, |sum, corner| sum + corner) / 5.0;
+        }
+
+        let top = trace(x + half, y);
+        let left = trace(x, y + half);
+        let right = trace(x + size, y + half);
+        let bottom = trace(x + half, y + size);
+        *samples += 4;
+
+        let [top_left, top_right, bottom_left, bottom_right] = corners;
+        let quarters = [
+            (
+                (x, y),
+                [top_left, top.clone(), left.clone(), center.clone()],
+            ),
+            (
+                (x + half, y),
+                [top, top_right, center.clone(), right.clone()],
+            ),
+            (
+                (x, y + half),
+                [left, center.clone(), bottom_left, bottom.clone()],
+            ),
+            ((x + half, y + half), [center, right, bottom, bottom_right]),
+        ];
+
+        let mut sum = Color::new(0.0, 0.0, 0.0);
+        for (origin, corners) in quarters {
+            sum = sum + self.sample_square(trace, origin, half, corners, depth + 1, samples);
+        }
+        sum / 4.0
+    }
+
+    fn contrasts(&self, a: &Color, b: &Color) -> bool {
+        (a.red - b.red).abs() > self.threshold
+            || (a.green - b.green).abs() > self.threshold
+            || (a.blue - b.blue).abs() > self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        render::{self, Antialiasing, RenderOptions, tests::default_camera},
+        world,
+    };
+    use std::sync::atomic::AtomicBool;
+
+    fn render(camera: &Camera, adaptive: Adaptive, threads: usize) -> render::RenderOutput {
+        let options = RenderOptions::new()
+            .with_tile_size(8)
+            .with_threads(threads)
+            .with_antialiasing(Antialiasing::Adaptive(adaptive));
+        render::render_progressive(
+            camera,
+            &world::default_world(),
+            &options,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+    }
+
+    #[test]
+    fn contrast_is_measured_per_component() {
+        let adaptive = Adaptive::new(0.1, 2);
+        let gray = Color::new(0.5, 0.5, 0.5);
+
+        assert!(!adaptive.contrasts(&gray, &Color::new(0.55, 0.45, 0.6)));
+        assert!(adaptive.contrasts(&gray, &Color::new(0.5, 0.5, 0.65)));
+        assert!(adaptive.contrasts(&gray, &Color::new(0.35, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn flat_square_is_not_subdivided() {
+        let adaptive = Adaptive::new(0.1, 3);
+        let red = Color::new(1.0, 0.0, 0.0);
+        let corners = [red.clone(), red.clone(), red.clone(), red.clone()];
+        let mut samples = 1;
+
+        let color = adaptive.sample_square(
+            &|_, _| red.clone(),
+            (0.0, 0.0),
+            1.0,
+            corners,
+            0,
+            &mut samples,
+        );
+
+        assert_eq!(color, red);
+        assert_eq!(samples, 2);
+    }
+
+    #[test]
+    fn edge_is_subdivided_up_to_max_depth() {
+        // a vertical edge at x = 0.3, white on its left and black on its right
+        let trace = |x: f64, _| {
+            if x < 0.3 {
+                Color::new(1.0, 1.0, 1.0)
+            } else {
+                Color::new(0.0, 0.0, 0.0)
+            }
+        };
+        let corners = [
+            trace(0.0, 0.0),
+            trace(1.0, 0.0),
+            trace(0.0, 1.0),
+            trace(1.0, 1.0),
+        ];
+
+        let mut samples = 1;
+        let shallow = Adaptive::new(0.1, 0);
+        let color =
+            shallow.sample_square(&trace, (0.0, 0.0), 1.0, corners.clone(), 0, &mut samples);
+        assert_eq!(samples, 2);
+        assert_eq!(color, Color::new(0.4, 0.4, 0.4));
+
+        // each level splits the squares along the edge, and refines the
+        // estimate of the covered area
+        let mut samples = 1;
+        let deep = Adaptive::new(0.1, 4);
+        let color = deep.sample_square(&trace, (0.0, 0.0), 1.0, corners, 0, &mut samples);
+        assert!(samples > 2 + 8);
+        assert!((color.red - 0.3).abs() < 0.05);
+    }
+
+    #[test]
+    fn flat_areas_take_fewer_samples_than_edges() {
+        let output = render(&default_camera(32, 32), Adaptive::new(0.05, 2), 2);
+
+        // the corner of the image only sees the background
+        assert_eq!(output.samples_at(0, 0), Some(2));
+        let max = *output.samples.iter().max().unwrap();
+        assert!(max > 2);
+        assert!(max <= 2 + 8 + 4 * 8);
+        assert!(output.samples.iter().all(|&count| count >= 2));
+
+        let debug = output.sample_canvas();
+        assert_eq!(
+            *debug.get_color_at(0, 0).unwrap(),
+            Color::new(0.0, 0.0, 0.0)
+        );
+        let brightest = output
+            .samples
+            .iter()
+            .position(|&count| count == max)
+            .unwrap();
+        assert_eq!(
+            *debug.get_color_at(brightest % 32, brightest / 32).unwrap(),
+            Color::new(1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn adaptive_rendering_is_independent_of_thread_count() {
+        let camera = default_camera(21, 13);
+        let single = render(&camera, Adaptive::new(0.05, 3), 1);
+        let multi = render(&camera, Adaptive::new(0.05, 3), 5);
+
+        assert_eq!(single.samples, multi.samples);
+        for y in 0..13 {
+            for x in 0..21 {
+                assert_eq!(
+                    single.canvas.get_color_at(x, y),
+                    multi.canvas.get_color_at(x, y)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,455 @@
+use crate::{EPSILON, camera::Camera, draw::Color, world::World};
+
+/// How the samples of a pixel are laid out over the area of its filter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplePattern {
+    /// A regular grid; the sample count is rounded to the nearest square
+    Grid,
+    /// One random sample within each cell of a regular grid; the sample
+    /// count is rounded to the nearest square
+    Jittered,
+    /// A regular grid rotated by atan(1/2), so that no two samples share a
+    /// row or a column; the sample count is rounded to the nearest square
+    RotatedGrid,
+    /// The Halton sequence in bases 2 and 3
+    Halton,
+    /// The first two dimensions of the Sobol sequence
+    Sobol,
+}
+
+/// A reconstruction filter, weighting each sample by its distance to the
+/// center of the pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    /// Equal weights over the pixel itself
+    Box,
+    /// Weights decreasing linearly up to one pixel away from the center
+    Tent,
+    /// A Gaussian (with alpha = 2) truncated 1.5 pixels away from the center
+    Gaussian,
+    /// The Mitchell–Netravali cubic, with B = C = 1/3, reaching two pixels
+    /// away from the center
+    Mitchell,
+}
+
+impl Filter {
+    /// Returns how far from the pixel center the filter reaches, in pixels
+    pub fn radius(&self) -> f64 {
+        match self {
+            Filter::Box => 0.5,
+            Filter::Tent => 1.0,
+            Filter::Gaussian => 1.5,
+            Filter::Mitchell => 2.0,
+        }
+    }
+
+    /// Returns the weight of a sample at (dx, dy) pixels from the center
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.evaluate(dx) * self.evaluate(dy)
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        let x = x.abs();
+        let radius = self.radius();
+        if x > radius {
+            return 0.0;
+        }
+
+        match self {
+            Filter::Box => 1.0,
+            Filter::Tent => 1.0 - x,
+            Filter::Gaussian => {
+                const ALPHA: f64 = 2.0;
+                // shifted down so that the filter reaches zero at its edge
+                (-ALPHA * x * x).exp() - (-ALPHA * radius * radius).exp()
+            }
+            Filter::Mitchell => {
+                const B: f64 = 1.0 / 3.0;
+                const C: f64 = 1.0 / 3.0;
+                let (x2, x3) = (x * x, x * x * x);
+                if x < 1.0 {
+                    ((12.0 - 9.0 * B - 6.0 * C) * x3
+                        + (-18.0 + 12.0 * B + 6.0 * C) * x2
+                        + (6.0 - 2.0 * B))
+                        / 6.0
+                } else {
+                    ((-B - 6.0 * C) * x3
+                        + (6.0 * B + 30.0 * C) * x2
+                        + (-12.0 * B - 48.0 * C) * x
+                        + (8.0 * B + 24.0 * C))
+                        / 6.0
+                }
+            }
+        }
+    }
+}
+
+impl SamplePattern {
+    /// Returns the positions of about `count` samples within the unit
+    /// square, for the pixel at (px, py). Random patterns are seeded from
+    /// the pixel coordinates, so the same pixel always gets the same
+    /// samples.
+    pub fn points(&self, count: usize, px: usize, py: usize) -> Vec<(f64, f64)> {
+        let count = count.max(1);
+        let mut rng = Rng::for_pixel(px, py);
+
+        match self {
+            SamplePattern::Grid => grid(count, |i, j, side| {
+                ((i as f64 + 0.5) / side, (j as f64 + 0.5) / side)
+            }),
+            SamplePattern::Jittered => grid(count, |i, j, side| {
+                (
+                    (i as f64 + rng.next_f64()) / side,
+                    (j as f64 + rng.next_f64()) / side,
+                )
+            }),
+            SamplePattern::RotatedGrid => {
+                let (sin, cos) = 0.5_f64.atan().sin_cos();
+                grid(count, |i, j, side| {
+                    let x = (i as f64 + 0.5) / side - 0.5;
+                    let y = (j as f64 + 0.5) / side - 0.5;
+                    // wrapping around keeps the samples within the square
+                    (
+                        (x * cos - y * sin + 0.5).rem_euclid(1.0),
+                        (x * sin + y * cos + 0.5).rem_euclid(1.0),
+                    )
+                })
+            }
+            SamplePattern::Halton => {
+                let shift = (rng.next_f64(), rng.next_f64());
+                (1..=count)
+                    .map(|i| rotate((radical_inverse(i, 2), radical_inverse(i, 3)), shift))
+                    .collect()
+            }
+            SamplePattern::Sobol => {
+                let shift = (rng.next_f64(), rng.next_f64());
+                (0..count).map(|i| rotate(sobol(i as u32), shift)).collect()
+            }
+        }
+    }
+}
+
+/// Fixed rate anti-aliasing: every pixel is the filtered sum of the same
+/// number of rays, spread over the area of the filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Supersampling {
+    pub pattern: SamplePattern,
+    /// Number of rays per pixel. Grid based patterns round it to the
+    /// nearest square.
+    pub samples: usize,
+    pub filter: Filter,
+}
+
+impl Supersampling {
+    pub fn new(pattern: SamplePattern, samples: usize, filter: Filter) -> Self {
+        Self {
+            pattern,
+            samples,
+            filter,
+        }
+    }
+
+    /// Returns the number of rays traced for each pixel, once the grid
+    /// based patterns have rounded the requested count
+    pub fn rays_per_pixel(&self) -> usize {
+        match self.pattern {
+            SamplePattern::Grid | SamplePattern::Jittered | SamplePattern::RotatedGrid => {
+                grid_side(self.samples).pow(2)
+            }
+            SamplePattern::Halton | SamplePattern::Sobol => self.samples.max(1),
+        }
+    }
+
+    /// Computes the color of the pixel at (px, py) as the average of its
+    /// samples, weighted by the filter
+    pub(crate) fn color_at(&self, camera: &Camera, world: &World, px: usize, py: usize) -> Color {
+        let radius = self.filter.radius();
+        let points = self.pattern.points(self.samples, px, py);
+        let mut weighted = Color::new(0.0, 0.0, 0.0);
+        let mut total_weight = 0.0;
+        let mut sum = Color::new(0.0, 0.0, 0.0);
+
+        for (u, v) in &points {
+            let dx = (u - 0.5) * 2.0 * radius;
+            let dy = (v - 0.5) * 2.0 * radius;
+            let ray = camera.ray_for_point(px as f64 + 0.5 + dx, py as f64 + 0.5 + dy);
+            let color = world.color_at(&ray);
+            let weight = self.filter.weight(dx, dy);
+
+            weighted = weighted + color.clone() * weight;
+            total_weight += weight;
+            sum = sum + color;
+        }
+
+        // with few samples, all of them may land where the filter is zero
+        if total_weight.abs() < EPSILON {
+            return sum / points.len() as f64;
+        }
+        weighted / total_weight
+    }
+}
+
+/// Lays out the cells of the nearest square grid to `count` samples,
+/// calling `sample(column, row, side)` for each of them
+fn grid<F>(count: usize, mut sample: F) -> Vec<(f64, f64)>
+where
+    F: FnMut(usize, usize, f64) -> (f64, f64),
+{
+    let side = grid_side(count);
+    let mut points = Vec::with_capacity(side * side);
+
+    for j in 0..side {
+        for i in 0..side {
+            points.push(sample(i, j, side as f64));
+        }
+    }
+    points
+}
+
+/// Returns the side of the square grid with the nearest number of cells to
+/// `count`
+fn grid_side(count: usize) -> usize {
+    ((count as f64).sqrt().round() as usize).max(1)
+}
+
+/// Shifts a point by an offset, wrapping around the unit square
+/// (Cranley–Patterson rotation). This decorrelates the low discrepancy
+/// sequences of neighboring pixels while keeping their good distribution.
+fn rotate((x, y): (f64, f64), (dx, dy): (f64, f64)) -> (f64, f64) {
+    ((x + dx).fract(), (y + dy).fract())
+}
+
+/// Mirrors the digits of `i` in the given base around the decimal point
+fn radical_inverse(mut i: usize, base: usize) -> f64 {
+    let inverse_base = 1.0 / base as f64;
+    let mut factor = inverse_base;
+    let mut result = 0.0;
+
+    while i > 0 {
+        result += (i % base) as f64 * factor;
+        i /= base;
+        factor *= inverse_base;
+    }
+    result
+}
+
+/// Returns the i-th point of the two dimensional Sobol sequence
+fn sobol(i: u32) -> (f64, f64) {
+    const SCALE: f64 = 1.0 / (1u64 << 32) as f64;
+
+    // the first dimension is the van der Corput sequence in base 2
+    let x = i.reverse_bits();
+
+    // the second uses the direction numbers of the polynomial x + 1
+    let mut y = 0u32;
+    let mut direction = 1u32 << 31;
+    let mut bits = i;
+    while bits != 0 {
+        if bits & 1 != 0 {
+            y ^= direction;
+        }
+        bits >>= 1;
+        direction ^= direction >> 1;
+    }
+
+    (x as f64 * SCALE, y as f64 * SCALE)
+}
+
+/// A small SplitMix64 generator. Seeding it from the pixel coordinates makes
+/// random patterns independent of which thread renders which pixel.
+struct Rng(u64);
+
+impl Rng {
+    fn for_pixel(px: usize, py: usize) -> Self {
+        Self(((px as u64) << 32) ^ py as u64)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{render::tests::default_camera, world};
+
+    const PATTERNS: [SamplePattern; 5] = [
+        SamplePattern::Grid,
+        SamplePattern::Jittered,
+        SamplePattern::RotatedGrid,
+        SamplePattern::Halton,
+        SamplePattern::Sobol,
+    ];
+
+    #[test]
+    fn box_filter() {
+        assert_eq!(Filter::Box.weight(0.0, 0.0), 1.0);
+        assert_eq!(Filter::Box.weight(0.5, -0.5), 1.0);
+        assert_eq!(Filter::Box.weight(0.6, 0.0), 0.0);
+    }
+
+    #[test]
+    fn tent_filter() {
+        assert_eq!(Filter::Tent.weight(0.0, 0.0), 1.0);
+        assert_eq!(Filter::Tent.weight(0.5, 0.0), 0.5);
+        assert_eq!(Filter::Tent.weight(-0.5, 0.5), 0.25);
+        assert_eq!(Filter::Tent.weight(1.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn gaussian_filter() {
+        let center = Filter::Gaussian.weight(0.0, 0.0);
+
+        assert!(center > Filter::Gaussian.weight(0.5, 0.0));
+        assert!(Filter::Gaussian.weight(0.5, 0.0) > Filter::Gaussian.weight(1.0, 0.0));
+        assert!(Filter::Gaussian.weight(1.5, 0.0).abs() < EPSILON);
+        assert_eq!(Filter::Gaussian.weight(2.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn mitchell_filter() {
+        assert!((Filter::Mitchell.evaluate(0.0) - 16.0 / 18.0).abs() < EPSILON);
+        assert!((Filter::Mitchell.evaluate(1.0) - 1.0 / 18.0).abs() < EPSILON);
+        // the negative lobe sharpens edges
+        assert!(Filter::Mitchell.evaluate(1.5) < 0.0);
+        assert!(Filter::Mitchell.evaluate(2.0).abs() < EPSILON);
+        assert_eq!(Filter::Mitchell.evaluate(2.5), 0.0);
+    }
+
+    #[test]
+    fn regular_grid_pattern() {
+        let points = SamplePattern::Grid.points(4, 0, 0);
+        assert_eq!(
+            points,
+            vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+        );
+    }
+
+    #[test]
+    fn grid_patterns_round_count_to_square() {
+        assert_eq!(SamplePattern::Grid.points(0, 0, 0), vec![(0.5, 0.5)]);
+        assert_eq!(SamplePattern::Jittered.points(5, 0, 0).len(), 4);
+        assert_eq!(SamplePattern::RotatedGrid.points(8, 0, 0).len(), 9);
+        assert_eq!(SamplePattern::Halton.points(5, 0, 0).len(), 5);
+        assert_eq!(SamplePattern::Sobol.points(5, 0, 0).len(), 5);
+    }
+
+    #[test]
+    fn counting_rays_per_pixel() {
+        for pattern in PATTERNS {
+            for samples in [0, 1, 5, 8, 16] {
+                let supersampling = Supersampling::new(pattern, samples, Filter::Box);
+                assert_eq!(
+                    supersampling.rays_per_pixel(),
+                    pattern.points(samples, 0, 0).len(),
+                    "{pattern:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn patterns_stay_within_unit_square() {
+        for pattern in PATTERNS {
+            for (x, y) in pattern.points(16, 3, 7) {
+                assert!((0.0..1.0).contains(&x), "{pattern:?}: {x}");
+                assert!((0.0..1.0).contains(&y), "{pattern:?}: {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn patterns_are_the_same_for_a_given_pixel() {
+        for pattern in PATTERNS {
+            assert_eq!(pattern.points(9, 12, 34), pattern.points(9, 12, 34));
+        }
+        assert_ne!(
+            SamplePattern::Jittered.points(9, 12, 34),
+            SamplePattern::Jittered.points(9, 34, 12)
+        );
+    }
+
+    #[test]
+    fn jittered_samples_stay_in_their_cell() {
+        let points = SamplePattern::Jittered.points(9, 5, 5);
+
+        for (index, (x, y)) in points.iter().enumerate() {
+            assert_eq!((x * 3.0) as usize, index % 3);
+            assert_eq!((y * 3.0) as usize, index / 3);
+        }
+    }
+
+    #[test]
+    fn rotated_grid_samples_have_distinct_rows_and_columns() {
+        let points = SamplePattern::RotatedGrid.points(4, 0, 0);
+        let mut xs: Vec<_> = points.iter().map(|(x, _)| (x * 1e6) as i64).collect();
+        let mut ys: Vec<_> = points.iter().map(|(_, y)| (y * 1e6) as i64).collect();
+        xs.sort();
+        xs.dedup();
+        ys.sort();
+        ys.dedup();
+
+        assert_eq!(xs.len(), 4);
+        assert_eq!(ys.len(), 4);
+    }
+
+    #[test]
+    fn radical_inverse_in_base_2_and_3() {
+        assert_eq!(radical_inverse(1, 2), 0.5);
+        assert_eq!(radical_inverse(2, 2), 0.25);
+        assert_eq!(radical_inverse(3, 2), 0.75);
+        assert!((radical_inverse(1, 3) - 1.0 / 3.0).abs() < EPSILON);
+        assert!((radical_inverse(5, 3) - 7.0 / 9.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn first_sobol_points() {
+        assert_eq!(sobol(0), (0.0, 0.0));
+        assert_eq!(sobol(1), (0.5, 0.5));
+        assert_eq!(sobol(2), (0.25, 0.75));
+        assert_eq!(sobol(3), (0.75, 0.25));
+    }
+
+    #[test]
+    fn single_sample_is_the_pixel_center() {
+        let world = world::default_world();
+        let camera = default_camera(11, 11);
+        let supersampling = Supersampling::new(SamplePattern::Grid, 1, Filter::Box);
+        let color = supersampling.color_at(&camera, &world, 5, 5);
+
+        assert_eq!(color, Color::new(0.38066, 0.47583, 0.2855));
+    }
+
+    #[test]
+    fn supersampling_blends_edges() {
+        let world = world::default_world();
+        let camera = default_camera(11, 11);
+
+        // find a pixel on the left silhouette of the sphere, with its center
+        // outside but its right edge inside
+        let black = Color::new(0.0, 0.0, 0.0);
+        let hits = |x: f64, y: f64| world.color_at(&camera.ray_for_point(x, y)) != black;
+        let (x, y) = (0..11)
+            .flat_map(|y| (0..5).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                !hits(x as f64 + 0.5, y as f64 + 0.5) && hits(x as f64 + 1.0, y as f64 + 0.5)
+            })
+            .unwrap();
+
+        for pattern in PATTERNS {
+            let supersampling = Supersampling::new(pattern, 16, Filter::Tent);
+            let color = supersampling.color_at(&camera, &world, x, y);
+            assert!(color.green > 0.0, "{pattern:?}");
+        }
+    }
+}
@@ -0,0 +1,434 @@
+mod cone;
+mod csg;
+mod cube;
+mod cylinder;
+mod group;
+mod plane;
+mod sphere;
+mod triangle;
+
+pub use cone::Cone;
+pub use csg::{Csg, CsgOperation, intersection_allowed};
+pub use cube::Cube;
+pub use cylinder::Cylinder;
+pub use group::Group;
+pub use plane::Plane;
+pub use sphere::Sphere;
+pub use triangle::{SmoothTriangle, Triangle};
+
+use crate::{
+    bounds::BoundingBox, intersection::Intersection, material::Material, matrix::Matrix4, ray::Ray,
+    tuple::Tuple,
+};
+use std::{any::Any, fmt, sync::Arc};
+
+/// A link in the chain of transforms leading from a shape up to the world.
+/// Containers such as groups hand one to each of their children, holding
+/// the container's own inverse transform and a link to its own parent.
+#[derive(Debug)]
+pub struct ParentTransform {
+    inverse: Matrix4,
+    parent: Option<Arc<ParentTransform>>,
+}
+
+impl ParentTransform {
+    /// Creates a link for a container with the given inverse transform,
+    /// itself nested in `parent`
+    pub fn new(inverse: Matrix4, parent: Option<Arc<ParentTransform>>) -> Self {
+        Self { inverse, parent }
+    }
+
+    /// Converts a point from world space into the container's space,
+    /// walking up the parent chain first
+    pub fn world_to_object(&self, point: &Tuple) -> Tuple {
+        match &self.parent {
+            Some(parent) => &self.inverse * &parent.world_to_object(point),
+            None => &self.inverse * point,
+        }
+    }
+
+    /// Converts a normal from the container's space into world space,
+    /// walking up the parent chain last
+    pub fn normal_to_world(&self, normal: &Tuple) -> Tuple {
+        let normal = transform_normal(&self.inverse, normal);
+        match &self.parent {
+            Some(parent) => parent.normal_to_world(&normal),
+            None => normal,
+        }
+    }
+}
+
+/// Transforms a normal using the transpose of the given inverse transform,
+/// returning a normalized vector
+fn transform_normal(inverse: &Matrix4, normal: &Tuple) -> Tuple {
+    let (x, y, z, _) = (inverse.clone().transpose() * normal).get();
+    Tuple::new_vector(x, y, z).normalize()
+}
+
+/// State shared by every shape: its object-to-world transform (and cached
+/// inverse), its surface material, whether it casts shadows, and the
+/// transforms of the containers it is nested in
+#[derive(Debug, Clone)]
+pub struct ShapeData {
+    transform: Matrix4,
+    inverse: Matrix4,
+    material: Material,
+    casts_shadows: bool,
+    parent: Option<Arc<ParentTransform>>,
+}
+
+impl ShapeData {
+    /// Creates shape state with an identity transform, the default material,
+    /// and shadow casting enabled
+    pub fn new() -> Self {
+        Self {
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            material: Material::new(),
+            casts_shadows: true,
+            parent: None,
+        }
+    }
+
+    /// Sets the transformation matrix and caches its inverse
+    /// ## Panics
+    /// Will panic if the matrix is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.inverse = transform
+            .clone()
+            .inverse()
+            .expect("Shape transform should be invertible");
+        self.transform = transform;
+    }
+
+    /// Sets the transform chain of the containers the shape is nested in
+    pub fn set_parent(&mut self, parent: Option<Arc<ParentTransform>>) {
+        self.parent = parent;
+    }
+
+    /// Creates the link handed to the children of a container owning this
+    /// state, so they can walk up to the world through it
+    pub fn link(&self) -> Arc<ParentTransform> {
+        Arc::new(ParentTransform::new(
+            self.inverse.clone(),
+            self.parent.clone(),
+        ))
+    }
+}
+
+impl Default for ShapeData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A primitive that can be intersected by rays. Implementors only describe
+/// the shape in its own object space through `local_intersect` and
+/// `local_normal_at`; the provided `intersect` and `normal_at` take care of
+/// converting between world space and object space.
+pub trait Shape: Any + fmt::Debug + Send + Sync {
+    /// Returns the state shared by every shape
+    fn data(&self) -> &ShapeData;
+
+    /// Returns the state shared by every shape, mutably
+    fn data_mut(&mut self) -> &mut ShapeData;
+
+    /// Intersects a ray, already transformed into object space, with the
+    /// shape
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>>;
+
+    /// Computes the (not necessarily normalized) surface normal at a point
+    /// in object space
+    fn local_normal_at(&self, local_point: &Tuple) -> Tuple;
+
+    /// Returns the box containing the shape, in object space
+    fn bounds(&self) -> BoundingBox;
+
+    /// Returns the box containing the shape, in the space of its parent
+    /// (that is, with the shape's own transform applied)
+    fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.transform())
+    }
+
+    /// Reorganizes the shapes contained in this one (if any) into a
+    /// hierarchy of groups holding at least `threshold` shapes each, so
+    /// rays can skip whole subgroups by missing their bounding box
+    fn divide(&mut self, _threshold: usize) {}
+
+    /// Computes the surface normal at a point in object space, for the
+    /// given intersection. Shapes whose normal depends on where they were
+    /// hit (such as smooth triangles) override this.
+    fn local_normal_at_hit(&self, local_point: &Tuple, _hit: &Intersection) -> Tuple {
+        self.local_normal_at(local_point)
+    }
+
+    /// Returns the object-to-world transformation matrix of the shape
+    fn transform(&self) -> &Matrix4 {
+        &self.data().transform
+    }
+
+    /// Returns the inverse of the shape's transformation matrix
+    fn inverse(&self) -> &Matrix4 {
+        &self.data().inverse
+    }
+
+    /// Sets the object-to-world transformation matrix of the shape
+    /// ## Panics
+    /// Will panic if the matrix is not invertible.
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.data_mut().set_transform(transform);
+    }
+
+    /// Returns the transform chain of the containers this shape is nested
+    /// in, if any
+    fn parent(&self) -> Option<&Arc<ParentTransform>> {
+        self.data().parent.as_ref()
+    }
+
+    /// Nests the shape in a container. Containers override this to pass the
+    /// updated chain on to their own children.
+    fn set_parent(&mut self, parent: Option<Arc<ParentTransform>>) {
+        self.data_mut().set_parent(parent);
+    }
+
+    /// Whether `other` is this shape, or one of the shapes it contains
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+
+    /// Returns the surface material of the shape
+    fn material(&self) -> &Material {
+        &self.data().material
+    }
+
+    /// Sets the surface material of the shape
+    fn set_material(&mut self, material: Material) {
+        self.data_mut().material = material;
+    }
+
+    /// Whether the shape blocks light from reaching the surfaces behind it
+    fn casts_shadows(&self) -> bool {
+        self.data().casts_shadows
+    }
+
+    /// Sets whether the shape blocks light from reaching the surfaces
+    /// behind it. Useful for light fixtures and glass panes.
+    fn set_casts_shadows(&mut self, casts_shadows: bool) {
+        self.data_mut().casts_shadows = casts_shadows;
+    }
+
+    /// Intersects a (world space) ray with the shape, returning the
+    /// intersections sorted by t
+    fn intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let local_ray = ray.transform(self.inverse());
+        self.local_intersect(&local_ray)
+    }
+
+    /// Whether the (world space) ray hits a shadow casting part of the
+    /// shape between its origin and `distance`. Used for shadow rays, which
+    /// only need to know whether anything is hit, not what is hit first.
+    fn occludes(&self, ray: &Ray, distance: f64) -> bool {
+        self.intersect(ray)
+            .iter()
+            .any(|i| i.object.casts_shadows() && i.t >= 0.0 && i.t < distance)
+    }
+
+    /// Converts a point from world space to object space, going through
+    /// the transforms of every container the shape is nested in
+    fn world_to_object(&self, point: &Tuple) -> Tuple {
+        match self.parent() {
+            Some(parent) => self.inverse() * &parent.world_to_object(point),
+            None => self.inverse() * point,
+        }
+    }
+
+    /// Converts a normal from object space to world space, going through
+    /// the transforms of every container the shape is nested in
+    fn normal_to_world(&self, normal: &Tuple) -> Tuple {
+        let normal = transform_normal(self.inverse(), normal);
+        match self.parent() {
+            Some(parent) => parent.normal_to_world(&normal),
+            None => normal,
+        }
+    }
+
+    /// Computes the normalized surface normal at a point in world space.
+    /// The normal is computed in object space, then brought back into world
+    /// space using the transpose of the inverse transforms.
+    fn normal_at(&self, world_point: &Tuple) -> Tuple {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at(&local_point);
+        self.normal_to_world(&local_normal)
+    }
+
+    /// Computes the normalized surface normal at a point in world space, for
+    /// the given intersection with the shape
+    fn normal_at_hit(&self, world_point: &Tuple, hit: &Intersection) -> Tuple {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at_hit(&local_point, hit);
+        self.normal_to_world(&local_normal)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::transform;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+    use std::sync::Mutex;
+
+    /// A shape recording the object space ray it was intersected with
+    #[derive(Debug)]
+    pub(crate) struct TestShape {
+        data: ShapeData,
+        pub(crate) saved_ray: Mutex<Option<Ray>>,
+    }
+
+    impl TestShape {
+        pub(crate) fn new() -> Self {
+            Self {
+                data: ShapeData::new(),
+                saved_ray: Mutex::new(None),
+            }
+        }
+    }
+
+    impl Shape for TestShape {
+        fn data(&self) -> &ShapeData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut ShapeData {
+            &mut self.data
+        }
+
+        fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+            *self.saved_ray.lock().unwrap() = Some(local_ray.clone());
+            vec![]
+        }
+
+        fn local_normal_at(&self, local_point: &Tuple) -> Tuple {
+            let (x, y, z, _) = local_point.get();
+            Tuple::new_vector(x, y, z)
+        }
+
+        fn bounds(&self) -> BoundingBox {
+            BoundingBox::new(
+                Tuple::new_point(-1.0, -1.0, -1.0),
+                Tuple::new_point(1.0, 1.0, 1.0),
+            )
+        }
+    }
+
+    #[test]
+    fn default_transformation() {
+        let shape = TestShape::new();
+        assert_eq!(*shape.transform(), Matrix4::identity());
+    }
+
+    #[test]
+    fn assigning_a_transformation() {
+        let mut shape = TestShape::new();
+        shape.set_transform(transform::translation(2.0, 3.0, 4.0));
+        assert_eq!(*shape.transform(), transform::translation(2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn default_material() {
+        let shape = TestShape::new();
+        assert_eq!(*shape.material(), Material::new());
+    }
+
+    #[test]
+    fn assigning_a_material() {
+        let mut shape = TestShape::new();
+        let mut material = Material::new();
+        material.ambient = 1.0;
+        shape.set_material(material.clone());
+
+        assert_eq!(*shape.material(), material);
+    }
+
+    #[test]
+    fn intersecting_scaled_shape_with_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut shape = TestShape::new();
+        shape.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        shape.intersect(&ray);
+        let saved_ray = shape.saved_ray.lock().unwrap().clone().unwrap();
+
+        assert_eq!(saved_ray.origin, Tuple::new_point(0.0, 0.0, -2.5));
+        assert_eq!(saved_ray.direction, Tuple::new_vector(0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn intersecting_translated_shape_with_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut shape = TestShape::new();
+        shape.set_transform(transform::translation(5.0, 0.0, 0.0));
+        shape.intersect(&ray);
+        let saved_ray = shape.saved_ray.lock().unwrap().clone().unwrap();
+
+        assert_eq!(saved_ray.origin, Tuple::new_point(-5.0, 0.0, -5.0));
+        assert_eq!(saved_ray.direction, Tuple::new_vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_on_translated_shape() {
+        let mut shape = TestShape::new();
+        shape.set_transform(transform::translation(0.0, 1.0, 0.0));
+        let normal = shape.normal_at(&Tuple::new_point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_eq!(
+            normal,
+            Tuple::new_vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    fn normal_on_transformed_shape() {
+        let mut shape = TestShape::new();
+        shape.set_transform(transform::scaling(1.0, 0.5, 1.0) * transform::rotation_z(PI / 5.0));
+        let normal = shape.normal_at(&Tuple::new_point(
+            0.0,
+            f64::sqrt(2.0) / 2.0,
+            -f64::sqrt(2.0) / 2.0,
+        ));
+
+        assert_eq!(normal, Tuple::new_vector(0.0, 0.97014, -0.24254));
+    }
+
+    #[test]
+    // Querying a shape's bounding box in its parent's space
+    fn parent_space_bounds() {
+        let mut shape = TestShape::new();
+        shape.set_transform(
+            transform::translation(1.0, -3.0, 5.0) * transform::scaling(0.5, 2.0, 4.0),
+        );
+        let bounds = shape.parent_space_bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(0.5, -5.0, 1.0));
+        assert_eq!(bounds.max, Tuple::new_point(1.5, -1.0, 9.0));
+    }
+
+    #[test]
+    fn shape_has_no_parent_by_default() {
+        let shape = TestShape::new();
+        assert!(shape.parent().is_none());
+    }
+
+    #[test]
+    fn shape_casts_shadows_by_default() {
+        let mut shape = TestShape::new();
+        assert!(shape.casts_shadows());
+
+        shape.set_casts_shadows(false);
+        assert!(!shape.casts_shadows());
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    EPSILON,
+    bounds::BoundingBox,
+    intersection::{self, Intersection},
+    ray::Ray,
+    shape::{Shape, ShapeData, cylinder},
+    tuple::Tuple,
+};
+
+/// A double-napped cone around the y axis, with its tip at the origin and a
+/// radius equal to |y| at every height. It is infinite by default, and may
+/// be truncated between `minimum` and `maximum` (both exclusive) on the y
+/// axis, with `closed` capping both ends.
+#[derive(Debug, Clone)]
+pub struct Cone {
+    data: ShapeData,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cone {
+    /// Creates an infinite, open double-napped cone
+    pub fn new() -> Self {
+        Self {
+            data: ShapeData::new(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+
+    /// Creates a cone truncated between y = minimum and y = maximum,
+    /// optionally capped at both ends
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            data: ShapeData::new(),
+            minimum,
+            maximum,
+            closed,
+        }
+    }
+
+    /// Intersects the ray with the end caps, if the cone is closed. The
+    /// radius of each cap is the absolute value of its y coordinate.
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        let (_, origin_y, _, _) = ray.origin.get();
+        let (_, direction_y, _, _) = ray.direction.get();
+
+        if !self.closed || direction_y.abs() < EPSILON {
+            return;
+        }
+
+        for cap in [self.minimum, self.maximum] {
+            let t = (cap - origin_y) / direction_y;
+            if cylinder::check_cap(ray, t, cap.abs()) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shape for Cone {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        let (origin_x, origin_y, origin_z, _) = local_ray.origin.get();
+        let (direction_x, direction_y, direction_z, _) = local_ray.direction.get();
+        let mut xs = vec![];
+
+        let a = direction_x * direction_x - direction_y * direction_y + direction_z * direction_z;
+        let b = 2.0 * origin_x * direction_x - 2.0 * origin_y * direction_y
+            + 2.0 * origin_z * direction_z;
+        let c = origin_x * origin_x - origin_y * origin_y + origin_z * origin_z;
+
+        let mut ts = vec![];
+        if a.abs() < EPSILON {
+            // the ray is parallel to one of the cone's halves, so it hits
+            // the other half at most once (and misses when b is zero too)
+            if b.abs() >= EPSILON {
+                ts.push(-c / (2.0 * b));
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return vec![];
+            }
+
+            let sqrt_disc = discriminant.sqrt();
+            let t0 = (-b - sqrt_disc) / (2.0 * a);
+            let t1 = (-b + sqrt_disc) / (2.0 * a);
+            ts.extend([t0.min(t1), t0.max(t1)]);
+        }
+
+        for t in ts {
+            let y = origin_y + t * direction_y;
+            if self.minimum < y && y < self.maximum {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+
+        self.intersect_caps(local_ray, &mut xs);
+        intersection::intersections(xs)
+    }
+
+    fn local_normal_at(&self, local_point: &Tuple) -> Tuple {
+        let (x, y, z, _) = local_point.get();
+        // square of the distance from the y axis
+        let dist = x * x + z * z;
+
+        if dist < y * y && y >= self.maximum - EPSILON {
+            Tuple::new_vector(0.0, 1.0, 0.0)
+        } else if dist < y * y && y <= self.minimum + EPSILON {
+            Tuple::new_vector(0.0, -1.0, 0.0)
+        } else {
+            let normal_y = if y > 0.0 { -dist.sqrt() } else { dist.sqrt() };
+            Tuple::new_vector(x, normal_y, z)
+        }
+    }
+
+    /// The radius of a cone is |y|, so it is widest at whichever end is
+    /// furthest from the tip
+    fn bounds(&self) -> BoundingBox {
+        let radius = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            Tuple::new_point(-radius, self.minimum, -radius),
+            Tuple::new_point(radius, self.maximum, radius),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Intersecting a cone with a ray
+    fn intersecting_cone_with_ray() {
+        let cone = Cone::new();
+        let cases = [
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 5.0, 5.0),
+            ((0.0, 0.0, -5.0), (1.0, 1.0, 1.0), 8.66025, 8.66025),
+            ((1.0, 1.0, -5.0), (-0.5, -1.0, 1.0), 4.55006, 49.44994),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), t0, t1) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            let xs = cone.local_intersect(&ray);
+
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t - t0).abs() < 1e-4);
+            assert!((xs[1].t - t1).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    // Intersecting a cone with a ray parallel to one of its halves
+    fn intersecting_cone_with_ray_parallel_to_half() {
+        let cone = Cone::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -1.0),
+            Tuple::new_vector(0.0, 1.0, 1.0).normalize(),
+        );
+        let xs = cone.local_intersect(&ray);
+
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].t - 0.35355).abs() < EPSILON);
+    }
+
+    #[test]
+    // A ray parallel to a half and through the tip misses the cone
+    fn ray_parallel_to_half_through_tip() {
+        let cone = Cone::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, -1.0, -1.0),
+            Tuple::new_vector(0.0, 1.0, 1.0).normalize(),
+        );
+
+        assert!(cone.local_intersect(&ray).is_empty());
+    }
+
+    #[test]
+    // Intersecting a cone's end caps
+    fn intersecting_cone_end_caps() {
+        let cone = Cone::truncated(-0.5, 0.5, true);
+        let cases = [
+            ((0.0, 0.0, -5.0), (0.0, 1.0, 0.0), 0),
+            ((0.0, 0.0, -0.25), (0.0, 1.0, 1.0), 2),
+            ((0.0, 0.0, -0.25), (0.0, 1.0, 0.0), 4),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), count) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            assert_eq!(cone.local_intersect(&ray).len(), count);
+        }
+    }
+
+    #[test]
+    // Computing the normal vector on a cone
+    fn normal_on_cone() {
+        let cone = Cone::new();
+        let cases = [
+            ((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)),
+            ((1.0, 1.0, 1.0), (1.0, -f64::sqrt(2.0), 1.0)),
+            ((-1.0, -1.0, 0.0), (-1.0, 1.0, 0.0)),
+        ];
+
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let normal = cone.local_normal_at(&Tuple::new_point(px, py, pz));
+            assert_eq!(normal, Tuple::new_vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn normal_on_cone_end_caps() {
+        let cone = Cone::truncated(-1.0, 2.0, true);
+
+        assert_eq!(
+            cone.local_normal_at(&Tuple::new_point(0.5, 2.0, 0.0)),
+            Tuple::new_vector(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            cone.local_normal_at(&Tuple::new_point(0.0, -1.0, 0.5)),
+            Tuple::new_vector(0.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn unbounded_cone_has_bounding_box() {
+        let bounds = Cone::new().bounds();
+
+        assert_eq!(
+            bounds.min.get(),
+            (f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY, 1.0)
+        );
+        assert_eq!(
+            bounds.max.get(),
+            (f64::INFINITY, f64::INFINITY, f64::INFINITY, 1.0)
+        );
+    }
+
+    #[test]
+    fn bounded_cone_has_bounding_box() {
+        let bounds = Cone::truncated(-5.0, 3.0, false).bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-5.0, -5.0, -5.0));
+        assert_eq!(bounds.max, Tuple::new_point(5.0, 3.0, 5.0));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{self, Intersection},
+    matrix::Matrix4,
+    ray::Ray,
+    shape::{ParentTransform, Shape, ShapeData},
+    tuple::Tuple,
+};
+use std::sync::Arc;
+
+/// The ways a CSG shape can combine its two children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    /// Everything in either child
+    Union,
+    /// Only what is in both children
+    Intersection,
+    /// What is in the left child, but not in the right one
+    Difference,
+}
+
+/// A shape built by combining two shapes with a set operation (constructive
+/// solid geometry). Either child may itself be a group or another CSG
+/// shape.
+#[derive(Debug)]
+pub struct Csg {
+    data: ShapeData,
+    operation: CsgOperation,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+    /// Box containing both children
+    bounds: BoundingBox,
+}
+
+impl Csg {
+    /// Creates a CSG shape, becoming the parent of both children
+    pub fn new(operation: CsgOperation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        let mut bounds = left.parent_space_bounds();
+        bounds.add_box(&right.parent_space_bounds());
+        let mut csg = Self {
+            data: ShapeData::new(),
+            operation,
+            left,
+            right,
+            bounds,
+        };
+        csg.link_children();
+        csg
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    /// Keeps only the intersections lying on the surface of the combined
+    /// shape. `xs` must be sorted by t.
+    pub fn filter_intersections<'a>(&self, xs: &[Intersection<'a>]) -> Vec<Intersection<'a>> {
+        // whether the ray is currently inside each child
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = vec![];
+
+        for i in xs {
+            let lhit = self.left.includes(i.object);
+
+            if intersection_allowed(self.operation, lhit, inl, inr) {
+                result.push(*i);
+            }
+
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+        }
+        result
+    }
+
+    /// Hands an up-to-date parent link to both children
+    fn link_children(&mut self) {
+        let link = self.data.link();
+        self.left.set_parent(Some(link.clone()));
+        self.right.set_parent(Some(link));
+    }
+}
+
+/// Whether an intersection is part of the surface of a CSG shape, given
+/// whether it hits the left child (`lhit`, otherwise the right one), and
+/// whether the ray is inside the left (`inl`) and right (`inr`) children
+pub fn intersection_allowed(operation: CsgOperation, lhit: bool, inl: bool, inr: bool) -> bool {
+    match operation {
+        CsgOperation::Union => (lhit && !inr) || (!lhit && !inl),
+        CsgOperation::Intersection => (lhit && inr) || (!lhit && inl),
+        CsgOperation::Difference => (lhit && !inr) || (!lhit && inl),
+    }
+}
+
+impl Shape for Csg {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.data.set_transform(transform);
+        self.link_children();
+    }
+
+    fn set_parent(&mut self, parent: Option<Arc<ParentTransform>>) {
+        self.data.set_parent(parent);
+        self.link_children();
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.left.includes(other) || self.right.includes(other)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        if !self.bounds.intersects(local_ray) {
+            return vec![];
+        }
+        let mut xs = self.left.intersect(local_ray);
+        xs.extend(self.right.intersect(local_ray));
+        self.filter_intersections(&intersection::intersections(xs))
+    }
+
+    /// ## Panics
+    /// Always panics: normals are computed on the children that were hit.
+    fn local_normal_at(&self, _local_point: &Tuple) -> Tuple {
+        unreachable!("CSG shapes have no surface; normals are computed on their children")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shape::{Cube, Group, Sphere},
+        transform,
+    };
+
+    fn sphere_and_cube(operation: CsgOperation) -> Csg {
+        Csg::new(operation, Box::new(Sphere::new()), Box::new(Cube::new()))
+    }
+
+    #[test]
+    // CSG is created with an operation and two shapes
+    fn creating_csg() {
+        let csg = sphere_and_cube(CsgOperation::Union);
+
+        assert_eq!(csg.operation(), CsgOperation::Union);
+        assert!(csg.left().parent().is_some());
+        assert!(csg.right().parent().is_some());
+    }
+
+    #[test]
+    // Evaluating the rule for a CSG operation
+    fn evaluating_intersection_allowed() {
+        use CsgOperation::*;
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+
+        for (operation, lhit, inl, inr, expected) in cases {
+            assert_eq!(
+                intersection_allowed(operation, lhit, inl, inr),
+                expected,
+                "{operation:?} lhit: {lhit}, inl: {inl}, inr: {inr}"
+            );
+        }
+    }
+
+    #[test]
+    // Filtering a list of intersections
+    fn filtering_intersections() {
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+
+        for (operation, x0, x1) in cases {
+            let csg = sphere_and_cube(operation);
+            let xs = [
+                Intersection::new(1.0, csg.left()),
+                Intersection::new(2.0, csg.right()),
+                Intersection::new(3.0, csg.left()),
+                Intersection::new(4.0, csg.right()),
+            ];
+            let result = csg.filter_intersections(&xs);
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].t, xs[x0].t);
+            assert_eq!(result[1].t, xs[x1].t);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg_object() {
+        let csg = sphere_and_cube(CsgOperation::Union);
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 2.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert!(csg.local_intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg_object() {
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(0.0, 0.0, 0.5));
+        let csg = Csg::new(CsgOperation::Union, Box::new(Sphere::new()), Box::new(s2));
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = csg.local_intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert!(std::ptr::addr_eq(xs[0].object, csg.left()));
+        assert_eq!(xs[1].t, 6.5);
+        assert!(std::ptr::addr_eq(xs[1].object, csg.right()));
+    }
+
+    #[test]
+    // A difference whose left child is a group: the hole carved by the
+    // right child is only seen through the group's children
+    fn csg_with_group_child() {
+        let mut group = Group::new();
+        group.add_child(Box::new(Sphere::new()));
+        let mut hole = Cube::new();
+        hole.set_transform(
+            transform::translation(0.0, 0.0, -1.0) * transform::scaling(0.5, 0.5, 0.5),
+        );
+        let csg = Csg::new(CsgOperation::Difference, Box::new(group), Box::new(hole));
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = csg.intersect(&ray);
+
+        // the sphere's front is carved out, leaving the bottom of the hole
+        // and the back of the sphere
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.5);
+        assert!(std::ptr::addr_eq(xs[0].object, csg.right()));
+        assert_eq!(xs[1].t, 6.0);
+        assert!(csg.left().includes(xs[1].object));
+    }
+
+    #[test]
+    // A CSG shape has a bounding box that contains its children
+    fn csg_has_bounding_box() {
+        let left = Sphere::new();
+        let mut right = Sphere::new();
+        right.set_transform(transform::translation(2.0, 3.0, 4.0));
+        let csg = Csg::new(CsgOperation::Difference, Box::new(left), Box::new(right));
+        let bounds = csg.bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-1.0, -1.0, -1.0));
+        assert_eq!(bounds.max, Tuple::new_point(3.0, 4.0, 5.0));
+    }
+
+    #[test]
+    // Subdividing a CSG shape subdivides its children
+    fn subdividing_csg() {
+        let mut s1 = Sphere::new();
+        s1.set_transform(transform::translation(-1.5, 0.0, 0.0));
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(1.5, 0.0, 0.0));
+        let mut left = Group::new();
+        left.add_child(Box::new(s1));
+        left.add_child(Box::new(s2));
+
+        let mut s3 = Sphere::new();
+        s3.set_transform(transform::translation(0.0, 0.0, -1.5));
+        let mut s4 = Sphere::new();
+        s4.set_transform(transform::translation(0.0, 0.0, 1.5));
+        let mut right = Group::new();
+        right.add_child(Box::new(s3));
+        right.add_child(Box::new(s4));
+
+        let mut csg = Csg::new(CsgOperation::Difference, Box::new(left), Box::new(right));
+        csg.divide(1);
+
+        for child in [csg.left(), csg.right()] {
+            let group = (child as &dyn std::any::Any)
+                .downcast_ref::<Group>()
+                .unwrap();
+            assert_eq!(group.children().len(), 2);
+            for subgroup in group.children() {
+                let subgroup: &dyn std::any::Any = subgroup.as_ref();
+                assert_eq!(
+                    subgroup.downcast_ref::<Group>().unwrap().children().len(),
+                    1
+                );
+            }
+        }
+    }
+
+    #[test]
+    // Transforming a CSG shape moves both children
+    fn transforming_csg() {
+        let mut csg = sphere_and_cube(CsgOperation::Union);
+        csg.set_transform(transform::translation(0.0, 5.0, 0.0));
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 5.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = csg.intersect(&ray);
+        let normal = xs[0].object.normal_at(&ray.position(xs[0].t));
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(normal, Tuple::new_vector(0.0, 0.0, -1.0));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{
+    bounds::{BoundingBox, check_axis},
+    intersection::Intersection,
+    ray::Ray,
+    shape::{Shape, ShapeData},
+    tuple::Tuple,
+};
+
+/// An axis-aligned cube centered at the origin, extending from -1 to 1 on
+/// every axis
+#[derive(Debug, Clone, Default)]
+pub struct Cube {
+    data: ShapeData,
+}
+
+impl Cube {
+    /// Creates an axis-aligned cube with an identity transform
+    pub fn new() -> Self {
+        Self {
+            data: ShapeData::new(),
+        }
+    }
+}
+
+impl Shape for Cube {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// Slab method: the ray hits the cube when the largest entering t is
+    /// smaller than the smallest exiting t across the three axes
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        let (origin_x, origin_y, origin_z, _) = local_ray.origin.get();
+        let (direction_x, direction_y, direction_z, _) = local_ray.direction.get();
+
+        let (xtmin, xtmax) = check_axis(origin_x, direction_x, -1.0, 1.0);
+        let (ytmin, ytmax) = check_axis(origin_y, direction_y, -1.0, 1.0);
+        let (ztmin, ztmax) = check_axis(origin_z, direction_z, -1.0, 1.0);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            return vec![];
+        }
+        vec![Intersection::new(tmin, self), Intersection::new(tmax, self)]
+    }
+
+    /// The normal is perpendicular to the face the point lies on, which is
+    /// the axis of the point's component with the largest absolute value
+    fn local_normal_at(&self, local_point: &Tuple) -> Tuple {
+        let (x, y, z, _) = local_point.get();
+        let maxc = x.abs().max(y.abs()).max(z.abs());
+
+        if maxc == x.abs() {
+            Tuple::new_vector(x, 0.0, 0.0)
+        } else if maxc == y.abs() {
+            Tuple::new_vector(0.0, y, 0.0)
+        } else {
+            Tuple::new_vector(0.0, 0.0, z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::new_point(-1.0, -1.0, -1.0),
+            Tuple::new_point(1.0, 1.0, 1.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A ray intersects a cube
+    fn ray_intersects_cube() {
+        let cube = Cube::new();
+        let cases = [
+            // +x, -x, +y, -y, +z, -z, inside
+            ((5.0, 0.5, 0.0), (-1.0, 0.0, 0.0), 4.0, 6.0),
+            ((-5.0, 0.5, 0.0), (1.0, 0.0, 0.0), 4.0, 6.0),
+            ((0.5, 5.0, 0.0), (0.0, -1.0, 0.0), 4.0, 6.0),
+            ((0.5, -5.0, 0.0), (0.0, 1.0, 0.0), 4.0, 6.0),
+            ((0.5, 0.0, 5.0), (0.0, 0.0, -1.0), 4.0, 6.0),
+            ((0.5, 0.0, -5.0), (0.0, 0.0, 1.0), 4.0, 6.0),
+            ((0.0, 0.5, 0.0), (0.0, 0.0, 1.0), -1.0, 1.0),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), t1, t2) in cases {
+            let ray = Ray::new(Tuple::new_point(ox, oy, oz), Tuple::new_vector(dx, dy, dz));
+            let xs = cube.local_intersect(&ray);
+
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t, t1);
+            assert_eq!(xs[1].t, t2);
+        }
+    }
+
+    #[test]
+    // A ray misses a cube
+    fn ray_misses_cube() {
+        let cube = Cube::new();
+        let cases = [
+            ((-2.0, 0.0, 0.0), (0.2673, 0.5345, 0.8018)),
+            ((0.0, -2.0, 0.0), (0.8018, 0.2673, 0.5345)),
+            ((0.0, 0.0, -2.0), (0.5345, 0.8018, 0.2673)),
+            ((2.0, 0.0, 2.0), (0.0, 0.0, -1.0)),
+            ((0.0, 2.0, 2.0), (0.0, -1.0, 0.0)),
+            ((2.0, 2.0, 0.0), (-1.0, 0.0, 0.0)),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz)) in cases {
+            let ray = Ray::new(Tuple::new_point(ox, oy, oz), Tuple::new_vector(dx, dy, dz));
+            assert!(cube.local_intersect(&ray).is_empty());
+        }
+    }
+
+    #[test]
+    // A ray parallel to a face, starting exactly on the slab boundary,
+    // must not produce NaN intersections
+    fn ray_parallel_to_face_on_boundary() {
+        let cube = Cube::new();
+        let ray = Ray::new(
+            Tuple::new_point(-1.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = cube.local_intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 6.0);
+    }
+
+    #[test]
+    // The normal on the surface of a cube
+    fn normal_on_surface_of_cube() {
+        let cube = Cube::new();
+        let cases = [
+            ((1.0, 0.5, -0.8), (1.0, 0.0, 0.0)),
+            ((-1.0, -0.2, 0.9), (-1.0, 0.0, 0.0)),
+            ((-0.4, 1.0, -0.1), (0.0, 1.0, 0.0)),
+            ((0.3, -1.0, -0.7), (0.0, -1.0, 0.0)),
+            ((-0.6, 0.3, 1.0), (0.0, 0.0, 1.0)),
+            ((0.4, 0.4, -1.0), (0.0, 0.0, -1.0)),
+            ((1.0, 1.0, 1.0), (1.0, 0.0, 0.0)),
+            ((-1.0, -1.0, -1.0), (-1.0, 0.0, 0.0)),
+        ];
+
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let normal = cube.local_normal_at(&Tuple::new_point(px, py, pz));
+            assert_eq!(normal, Tuple::new_vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn cube_has_bounding_box() {
+        let bounds = Cube::new().bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-1.0, -1.0, -1.0));
+        assert_eq!(bounds.max, Tuple::new_point(1.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    EPSILON,
+    bounds::BoundingBox,
+    intersection::{self, Intersection},
+    ray::Ray,
+    shape::{Shape, ShapeData},
+    tuple::Tuple,
+};
+
+/// A cylinder of radius 1 around the y axis. It is infinitely long by
+/// default, and may be truncated between `minimum` and `maximum` (both
+/// exclusive) on the y axis, with `closed` capping both ends.
+#[derive(Debug, Clone)]
+pub struct Cylinder {
+    data: ShapeData,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cylinder {
+    /// Creates an infinitely long, open cylinder
+    pub fn new() -> Self {
+        Self {
+            data: ShapeData::new(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+
+    /// Creates a cylinder truncated between y = minimum and y = maximum,
+    /// optionally capped at both ends
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            data: ShapeData::new(),
+            minimum,
+            maximum,
+            closed,
+        }
+    }
+
+    /// Intersects the ray with the end caps, if the cylinder is closed
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        let (_, origin_y, _, _) = ray.origin.get();
+        let (_, direction_y, _, _) = ray.direction.get();
+
+        // caps only matter when the cylinder is closed, and might possibly
+        // be intersected by the ray
+        if !self.closed || direction_y.abs() < EPSILON {
+            return;
+        }
+
+        for cap in [self.minimum, self.maximum] {
+            let t = (cap - origin_y) / direction_y;
+            if check_cap(ray, t, 1.0) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks whether the intersection at t is within the given radius from
+/// the y axis
+pub(crate) fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let (x, _, z, _) = ray.position(t).get();
+    x * x + z * z <= radius * radius
+}
+
+impl Shape for Cylinder {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        let (origin_x, origin_y, origin_z, _) = local_ray.origin.get();
+        let (direction_x, direction_y, direction_z, _) = local_ray.direction.get();
+        let mut xs = vec![];
+
+        let a = direction_x * direction_x + direction_z * direction_z;
+
+        // a ray parallel to the y axis can only hit the caps
+        if a.abs() >= EPSILON {
+            let b = 2.0 * origin_x * direction_x + 2.0 * origin_z * direction_z;
+            let c = origin_x * origin_x + origin_z * origin_z - 1.0;
+            let discriminant = b * b - 4.0 * a * c;
+
+            if discriminant < 0.0 {
+                return vec![];
+            }
+
+            let sqrt_disc = discriminant.sqrt();
+            let t0 = (-b - sqrt_disc) / (2.0 * a);
+            let t1 = (-b + sqrt_disc) / (2.0 * a);
+
+            for t in [t0.min(t1), t0.max(t1)] {
+                let y = origin_y + t * direction_y;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        self.intersect_caps(local_ray, &mut xs);
+        intersection::intersections(xs)
+    }
+
+    fn local_normal_at(&self, local_point: &Tuple) -> Tuple {
+        let (x, y, z, _) = local_point.get();
+        // square of the distance from the y axis
+        let dist = x * x + z * z;
+
+        if dist < 1.0 && y >= self.maximum - EPSILON {
+            Tuple::new_vector(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && y <= self.minimum + EPSILON {
+            Tuple::new_vector(0.0, -1.0, 0.0)
+        } else {
+            Tuple::new_vector(x, 0.0, z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::new_point(-1.0, self.minimum, -1.0),
+            Tuple::new_point(1.0, self.maximum, 1.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A ray misses a cylinder
+    fn ray_misses_cylinder() {
+        let cylinder = Cylinder::new();
+        let cases = [
+            ((1.0, 0.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 0.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 0.0, -5.0), (1.0, 1.0, 1.0)),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz)) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            assert!(cylinder.local_intersect(&ray).is_empty());
+        }
+    }
+
+    #[test]
+    // A ray strikes a cylinder
+    fn ray_strikes_cylinder() {
+        let cylinder = Cylinder::new();
+        let cases = [
+            ((1.0, 0.0, -5.0), (0.0, 0.0, 1.0), 5.0, 5.0),
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 4.0, 6.0),
+            ((0.5, 0.0, -5.0), (0.1, 1.0, 1.0), 6.80798, 7.08872),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), t0, t1) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            let xs = cylinder.local_intersect(&ray);
+
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t - t0).abs() < EPSILON);
+            assert!((xs[1].t - t1).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    // Normal vector on a cylinder
+    fn normal_on_cylinder() {
+        let cylinder = Cylinder::new();
+        let cases = [
+            ((1.0, 0.0, 0.0), (1.0, 0.0, 0.0)),
+            ((0.0, 5.0, -1.0), (0.0, 0.0, -1.0)),
+            ((0.0, -2.0, 1.0), (0.0, 0.0, 1.0)),
+            ((-1.0, 1.0, 0.0), (-1.0, 0.0, 0.0)),
+        ];
+
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let normal = cylinder.local_normal_at(&Tuple::new_point(px, py, pz));
+            assert_eq!(normal, Tuple::new_vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    // The default minimum and maximum for a cylinder
+    fn default_minimum_and_maximum() {
+        let cylinder = Cylinder::new();
+
+        assert_eq!(cylinder.minimum, f64::NEG_INFINITY);
+        assert_eq!(cylinder.maximum, f64::INFINITY);
+        assert!(!cylinder.closed);
+    }
+
+    #[test]
+    // Intersecting a constrained cylinder
+    fn intersecting_constrained_cylinder() {
+        let cylinder = Cylinder::truncated(1.0, 2.0, false);
+        let cases = [
+            ((0.0, 1.5, 0.0), (0.1, 1.0, 0.0), 0),
+            ((0.0, 3.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 2.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 1.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 1.5, -2.0), (0.0, 0.0, 1.0), 2),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), count) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            assert_eq!(cylinder.local_intersect(&ray).len(), count);
+        }
+    }
+
+    #[test]
+    // Intersecting the caps of a closed cylinder
+    fn intersecting_caps_of_closed_cylinder() {
+        let cylinder = Cylinder::truncated(1.0, 2.0, true);
+        let cases = [
+            ((0.0, 3.0, 0.0), (0.0, -1.0, 0.0), 2),
+            ((0.0, 3.0, -2.0), (0.0, -1.0, 2.0), 2),
+            // corner case
+            ((0.0, 4.0, -2.0), (0.0, -1.0, 1.0), 2),
+            ((0.0, 0.0, -2.0), (0.0, 1.0, 2.0), 2),
+            // corner case
+            ((0.0, -1.0, -2.0), (0.0, 1.0, 1.0), 2),
+        ];
+
+        for ((ox, oy, oz), (dx, dy, dz), count) in cases {
+            let ray = Ray::new(
+                Tuple::new_point(ox, oy, oz),
+                Tuple::new_vector(dx, dy, dz).normalize(),
+            );
+            assert_eq!(cylinder.local_intersect(&ray).len(), count);
+        }
+    }
+
+    #[test]
+    // The normal vector on a cylinder's end caps
+    fn normal_on_end_caps() {
+        let cylinder = Cylinder::truncated(1.0, 2.0, true);
+        let cases = [
+            ((0.0, 1.0, 0.0), (0.0, -1.0, 0.0)),
+            ((0.5, 1.0, 0.0), (0.0, -1.0, 0.0)),
+            ((0.0, 1.0, 0.5), (0.0, -1.0, 0.0)),
+            ((0.0, 2.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.5, 2.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 2.0, 0.5), (0.0, 1.0, 0.0)),
+        ];
+
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let normal = cylinder.local_normal_at(&Tuple::new_point(px, py, pz));
+            assert_eq!(normal, Tuple::new_vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn unbounded_cylinder_has_bounding_box() {
+        let bounds = Cylinder::new().bounds();
+
+        assert_eq!(bounds.min.get(), (-1.0, f64::NEG_INFINITY, -1.0, 1.0));
+        assert_eq!(bounds.max.get(), (1.0, f64::INFINITY, 1.0, 1.0));
+    }
+
+    #[test]
+    fn bounded_cylinder_has_bounding_box() {
+        let bounds = Cylinder::truncated(-5.0, 3.0, false).bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-1.0, -5.0, -1.0));
+        assert_eq!(bounds.max, Tuple::new_point(1.0, 3.0, 1.0));
+    }
+}
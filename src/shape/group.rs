@@ -0,0 +1,545 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{self, Intersection},
+    matrix::Matrix4,
+    ray::Ray,
+    shape::{ParentTransform, Shape, ShapeData},
+    tuple::Tuple,
+};
+use std::sync::Arc;
+
+type Children = Vec<Box<dyn Shape>>;
+
+/// A container of shapes, transformed as a unit. The group's transform
+/// applies to every child on top of the child's own transform.
+#[derive(Debug, Default)]
+pub struct Group {
+    data: ShapeData,
+    children: Children,
+    /// Box containing every child, grown as children are added
+    bounds: BoundingBox,
+}
+
+impl Group {
+    /// Creates an empty group with an identity transform
+    pub fn new() -> Self {
+        Self {
+            data: ShapeData::new(),
+            children: vec![],
+            bounds: BoundingBox::empty(),
+        }
+    }
+
+    /// Adds a shape to the group, making the group its parent
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        child.set_parent(Some(self.data.link()));
+        self.bounds.add_box(&child.parent_space_bounds());
+        self.children.push(child);
+    }
+
+    /// Returns the shapes contained in the group
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Takes the shapes out of the group. They still refer to the group's
+    /// transform until they are added to another container.
+    pub fn into_children(self) -> Vec<Box<dyn Shape>> {
+        self.children
+    }
+
+    /// Splits the group's bounding box in two halves, and takes out the
+    /// children fitting entirely in each half. Children straddling both
+    /// halves stay in the group.
+    fn partition_children(&mut self) -> (Children, Children) {
+        let (left_bounds, right_bounds) = self.bounds.split();
+        let mut left = vec![];
+        let mut right = vec![];
+        let mut remaining = vec![];
+
+        for child in self.children.drain(..) {
+            let bounds = child.parent_space_bounds();
+            if left_bounds.contains_box(&bounds) {
+                left.push(child);
+            } else if right_bounds.contains_box(&bounds) {
+                right.push(child);
+            } else {
+                remaining.push(child);
+            }
+        }
+
+        self.children = remaining;
+        (left, right)
+    }
+
+    /// Moves the given shapes into a new group, added as a child
+    fn make_subgroup(&mut self, children: Children) {
+        let mut subgroup = Group::new();
+        for child in children {
+            subgroup.add_child(child);
+        }
+        self.add_child(Box::new(subgroup));
+    }
+
+    /// Hands an up-to-date parent link to every child, after the group's
+    /// transform or its own parent changed
+    fn link_children(&mut self) {
+        let link = self.data.link();
+        for child in self.children.iter_mut() {
+            child.set_parent(Some(link.clone()));
+        }
+    }
+}
+
+impl Shape for Group {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.data.set_transform(transform);
+        self.link_children();
+    }
+
+    fn set_parent(&mut self, parent: Option<Arc<ParentTransform>>) {
+        self.data.set_parent(parent);
+        self.link_children();
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.children.iter().any(|child| child.includes(other))
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    /// Recursively moves children into subgroups, as long as groups have at
+    /// least `threshold` children
+    fn divide(&mut self, threshold: usize) {
+        let count = self.children.len();
+        if threshold <= count {
+            let (left, right) = self.partition_children();
+            // when every child ends up on the same side, splitting further
+            // would only nest the same children over and over
+            if left.len() == count || right.len() == count {
+                self.children = if left.is_empty() { right } else { left };
+            } else {
+                if !left.is_empty() {
+                    self.make_subgroup(left);
+                }
+                if !right.is_empty() {
+                    self.make_subgroup(right);
+                }
+            }
+        }
+
+        for child in self.children.iter_mut() {
+            child.divide(threshold);
+        }
+    }
+
+    /// Intersects the ray with every child, returning the intersections
+    /// sorted by t. The intersections refer to the children, never to the
+    /// group itself.
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        if !self.bounds.intersects(local_ray) {
+            return vec![];
+        }
+        let xs = self
+            .children
+            .iter()
+            .flat_map(|child| child.intersect(local_ray))
+            .collect();
+        intersection::intersections(xs)
+    }
+
+    /// ## Panics
+    /// Always panics: a group has no surface of its own, normals are
+    /// computed on the children that were hit.
+    fn local_normal_at(&self, _local_point: &Tuple) -> Tuple {
+        unreachable!("Groups have no surface; normals are computed on their children")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shape::{Cylinder, Sphere, tests::TestShape},
+        transform,
+    };
+    use std::f64::consts::PI;
+
+    #[test]
+    fn creating_a_new_group() {
+        let group = Group::new();
+
+        assert_eq!(*group.transform(), Matrix4::identity());
+        assert!(group.is_empty());
+    }
+
+    #[test]
+    fn adding_child_to_group() {
+        let mut group = Group::new();
+        group.add_child(Box::new(TestShape::new()));
+
+        assert_eq!(group.children().len(), 1);
+        assert!(group.children()[0].parent().is_some());
+    }
+
+    #[test]
+    fn group_includes_its_descendants() {
+        let mut inner = Group::new();
+        inner.add_child(Box::new(Sphere::new()));
+        let mut group = Group::new();
+        group.add_child(Box::new(inner));
+        let other = Sphere::new();
+
+        let inner: &dyn std::any::Any = group.children()[0].as_ref();
+        let sphere = inner.downcast_ref::<Group>().unwrap().children()[0].as_ref();
+
+        assert!(group.includes(sphere));
+        assert!(!group.includes(&other));
+    }
+
+    fn as_group(shape: &dyn Shape) -> &Group {
+        (shape as &dyn std::any::Any)
+            .downcast_ref()
+            .expect("Shape should be a group")
+    }
+
+    #[test]
+    // A group has a bounding box that contains its children
+    fn group_has_bounding_box() {
+        let mut sphere = Sphere::new();
+        sphere.set_transform(
+            transform::translation(2.0, 5.0, -3.0) * transform::scaling(2.0, 2.0, 2.0),
+        );
+        let mut cylinder = Cylinder::truncated(-2.0, 2.0, false);
+        cylinder.set_transform(
+            transform::translation(-4.0, -1.0, 4.0) * transform::scaling(0.5, 1.0, 0.5),
+        );
+        let mut group = Group::new();
+        group.add_child(Box::new(sphere));
+        group.add_child(Box::new(cylinder));
+        let bounds = group.bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-4.5, -3.0, -5.0));
+        assert_eq!(bounds.max, Tuple::new_point(4.0, 7.0, 4.5));
+    }
+
+    #[test]
+    // Intersecting a ray with a group doesn't test the children if the box
+    // is missed
+    fn missing_group_bounds_skips_children() {
+        let mut group = Group::new();
+        group.add_child(Box::new(TestShape::new()));
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        group.intersect(&ray);
+
+        let any: &dyn std::any::Any = group.children()[0].as_ref();
+        let child = any.downcast_ref::<TestShape>().unwrap();
+        assert!(child.saved_ray.lock().unwrap().is_none());
+    }
+
+    #[test]
+    // Intersecting a ray with a group tests the children if the box is hit
+    fn hitting_group_bounds_tests_children() {
+        let mut group = Group::new();
+        group.add_child(Box::new(TestShape::new()));
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        group.intersect(&ray);
+
+        let any: &dyn std::any::Any = group.children()[0].as_ref();
+        let child = any.downcast_ref::<TestShape>().unwrap();
+        assert!(child.saved_ray.lock().unwrap().is_some());
+    }
+
+    /// Three spheres: s1 at x = -2, s2 at x = 2, and s3 straddling the
+    /// middle
+    fn three_spheres() -> [Sphere; 3] {
+        let mut s1 = Sphere::new();
+        s1.set_transform(transform::translation(-2.0, 0.0, 0.0));
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(2.0, 0.0, 0.0));
+        let s3 = Sphere::new();
+        [s1, s2, s3]
+    }
+
+    #[test]
+    // Partitioning a group's children
+    fn partitioning_children() {
+        let mut group = Group::new();
+        for sphere in three_spheres() {
+            group.add_child(Box::new(sphere));
+        }
+        let (left, right) = group.partition_children();
+
+        assert_eq!(group.children().len(), 1);
+        assert_eq!(*group.children()[0].transform(), Matrix4::identity());
+        assert_eq!(left.len(), 1);
+        assert_eq!(*left[0].transform(), transform::translation(-2.0, 0.0, 0.0));
+        assert_eq!(right.len(), 1);
+        assert_eq!(*right[0].transform(), transform::translation(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    // Creating a subgroup from a list of children
+    fn creating_subgroup() {
+        let [s1, s2, _] = three_spheres();
+        let mut group = Group::new();
+        group.make_subgroup(vec![Box::new(s1), Box::new(s2)]);
+
+        assert_eq!(group.children().len(), 1);
+        assert_eq!(as_group(group.children()[0].as_ref()).children().len(), 2);
+    }
+
+    #[test]
+    // Subdividing a primitive does nothing
+    fn subdividing_primitive() {
+        let mut sphere = Sphere::new();
+        sphere.divide(1);
+
+        assert_eq!(*sphere.transform(), Matrix4::identity());
+    }
+
+    #[test]
+    // Subdividing a group partitions its children
+    fn subdividing_group() {
+        let mut s1 = Sphere::new();
+        s1.set_transform(transform::translation(-2.0, -2.0, 0.0));
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(-2.0, 2.0, 0.0));
+        let mut s3 = Sphere::new();
+        s3.set_transform(transform::scaling(4.0, 4.0, 4.0));
+        let mut group = Group::new();
+        group.add_child(Box::new(s1));
+        group.add_child(Box::new(s2));
+        group.add_child(Box::new(s3));
+        group.divide(1);
+
+        // s3 straddles both halves and stays, s1 and s2 end up in a
+        // subgroup, itself split in one subgroup each
+        assert_eq!(group.children().len(), 2);
+        assert_eq!(
+            *group.children()[0].transform(),
+            transform::scaling(4.0, 4.0, 4.0)
+        );
+        let subgroup = as_group(group.children()[1].as_ref());
+        assert_eq!(subgroup.children().len(), 2);
+        for (child, y) in subgroup.children().iter().zip([-2.0, 2.0]) {
+            let child = as_group(child.as_ref());
+            assert_eq!(child.children().len(), 1);
+            assert_eq!(
+                *child.children()[0].transform(),
+                transform::translation(-2.0, y, 0.0)
+            );
+        }
+    }
+
+    #[test]
+    // Subdividing a group with too few children
+    fn subdividing_group_with_too_few_children() {
+        let mut s1 = Sphere::new();
+        s1.set_transform(transform::translation(-2.0, 0.0, 0.0));
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(2.0, 1.0, 0.0));
+        let mut s3 = Sphere::new();
+        s3.set_transform(transform::translation(2.0, -1.0, 0.0));
+        let mut subgroup = Group::new();
+        subgroup.add_child(Box::new(s1));
+        subgroup.add_child(Box::new(s2));
+        subgroup.add_child(Box::new(s3));
+        let mut group = Group::new();
+        group.add_child(Box::new(subgroup));
+        group.add_child(Box::new(Sphere::new()));
+        group.divide(3);
+
+        let subgroup = as_group(group.children()[0].as_ref());
+        assert_eq!(group.children().len(), 2);
+        assert_eq!(subgroup.children().len(), 2);
+        assert_eq!(
+            as_group(subgroup.children()[0].as_ref()).children().len(),
+            1
+        );
+        assert_eq!(
+            as_group(subgroup.children()[1].as_ref()).children().len(),
+            2
+        );
+    }
+
+    #[test]
+    // Identical children can't be told apart by splitting, and are left
+    // as they are
+    fn subdividing_group_of_identical_children() {
+        let mut group = Group::new();
+        for _ in 0..4 {
+            group.add_child(Box::new(Sphere::new()));
+        }
+        group.divide(1);
+
+        assert_eq!(group.children().len(), 4);
+    }
+
+    #[test]
+    // Subdivided children still find their way back to the world
+    fn subdivided_children_keep_parent_transforms() {
+        let mut group = Group::new();
+        group.set_transform(transform::translation(0.0, 10.0, 0.0));
+        for sphere in three_spheres() {
+            group.add_child(Box::new(sphere));
+        }
+        group.divide(1);
+        let ray = Ray::new(
+            Tuple::new_point(-2.0, 10.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = group.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(
+            xs[0].object.normal_at(&ray.position(xs[0].t)),
+            Tuple::new_vector(0.0, 0.0, -1.0)
+        );
+    }
+
+    #[test]
+    fn intersecting_ray_with_empty_group() {
+        let group = Group::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert!(group.local_intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn intersecting_ray_with_nonempty_group() {
+        let mut group = Group::new();
+        let s1 = Sphere::new();
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::new();
+        s3.set_transform(transform::translation(5.0, 0.0, 0.0));
+        group.add_child(Box::new(s1));
+        group.add_child(Box::new(s2));
+        group.add_child(Box::new(s3));
+
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = group.local_intersect(&ray);
+        let s1 = group.children()[0].as_ref();
+        let s2 = group.children()[1].as_ref();
+
+        assert_eq!(xs.len(), 4);
+        assert!(std::ptr::addr_eq(xs[0].object, s2));
+        assert!(std::ptr::addr_eq(xs[1].object, s2));
+        assert!(std::ptr::addr_eq(xs[2].object, s1));
+        assert!(std::ptr::addr_eq(xs[3].object, s1));
+    }
+
+    #[test]
+    fn intersecting_transformed_group() {
+        let mut group = Group::new();
+        group.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::translation(5.0, 0.0, 0.0));
+        group.add_child(Box::new(sphere));
+
+        let ray = Ray::new(
+            Tuple::new_point(10.0, 0.0, -10.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert_eq!(group.intersect(&ray).len(), 2);
+    }
+
+    /// Builds g1 (rotated) > g2 (scaled) > sphere (translated)
+    fn nested_groups(scaling: Matrix4) -> Group {
+        let mut g1 = Group::new();
+        g1.set_transform(transform::rotation_y(PI / 2.0));
+        let mut g2 = Group::new();
+        g2.set_transform(scaling);
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(sphere));
+        g1.add_child(Box::new(g2));
+        g1
+    }
+
+    /// Finds the sphere nested in `nested_groups` by intersecting it
+    fn nested_sphere(g1: &Group) -> &dyn Shape {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -20.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        g1.intersect(&ray)[0].object
+    }
+
+    #[test]
+    fn converting_point_from_world_to_object_space() {
+        let g1 = nested_groups(transform::scaling(2.0, 2.0, 2.0));
+        let sphere = nested_sphere(&g1);
+        let point = sphere.world_to_object(&Tuple::new_point(-2.0, 0.0, -10.0));
+
+        assert_eq!(point, Tuple::new_point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn converting_normal_from_object_to_world_space() {
+        let g1 = nested_groups(transform::scaling(1.0, 2.0, 3.0));
+        let sphere = nested_sphere(&g1);
+        let k = f64::sqrt(3.0) / 3.0;
+        let normal = sphere.normal_to_world(&Tuple::new_vector(k, k, k));
+
+        assert_eq!(normal, Tuple::new_vector(0.28571, 0.42857, -0.85714));
+    }
+
+    #[test]
+    fn finding_normal_on_child_object() {
+        let g1 = nested_groups(transform::scaling(1.0, 2.0, 3.0));
+        let sphere = nested_sphere(&g1);
+        let normal = sphere.normal_at(&Tuple::new_point(1.7321, 1.1547, -5.5774));
+
+        let expected = Tuple::new_vector(0.2857, 0.42854, -0.85716);
+
+        assert!((Tuple::dot(&normal, &expected) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    // Children added before the group is transformed or nested still see
+    // the group's final transform
+    fn transforming_group_after_adding_children() {
+        let mut g2 = Group::new();
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(sphere));
+        g2.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let mut g1 = Group::new();
+        g1.add_child(Box::new(g2));
+        g1.set_transform(transform::rotation_y(PI / 2.0));
+
+        let sphere = nested_sphere(&g1);
+        let point = sphere.world_to_object(&Tuple::new_point(-2.0, 0.0, -10.0));
+
+        assert_eq!(point, Tuple::new_point(0.0, 0.0, -1.0));
+    }
+}
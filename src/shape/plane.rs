@@ -0,0 +1,135 @@
+use crate::{
+    EPSILON,
+    bounds::BoundingBox,
+    intersection::Intersection,
+    ray::Ray,
+    shape::{Shape, ShapeData},
+    tuple::Tuple,
+};
+
+/// An infinite plane spanning the x and z axes, passing through the origin
+#[derive(Debug, Clone, Default)]
+pub struct Plane {
+    data: ShapeData,
+}
+
+impl Plane {
+    /// Creates an xz plane with an identity transform
+    pub fn new() -> Self {
+        Self {
+            data: ShapeData::new(),
+        }
+    }
+}
+
+impl Shape for Plane {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// A ray parallel to (or coplanar with) the plane never intersects it
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        let (_, origin_y, _, _) = local_ray.origin.get();
+        let (_, direction_y, _, _) = local_ray.direction.get();
+
+        if direction_y.abs() < EPSILON {
+            return vec![];
+        }
+
+        vec![Intersection::new(-origin_y / direction_y, self)]
+    }
+
+    fn local_normal_at(&self, _local_point: &Tuple) -> Tuple {
+        Tuple::new_vector(0.0, 1.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::new_point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Tuple::new_point(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // The normal of a plane is constant everywhere
+    fn normal_is_constant_everywhere() {
+        let plane = Plane::new();
+        let n1 = plane.local_normal_at(&Tuple::new_point(0.0, 0.0, 0.0));
+        let n2 = plane.local_normal_at(&Tuple::new_point(10.0, 0.0, -10.0));
+        let n3 = plane.local_normal_at(&Tuple::new_point(-5.0, 0.0, 150.0));
+
+        assert_eq!(n1, Tuple::new_vector(0.0, 1.0, 0.0));
+        assert_eq!(n2, Tuple::new_vector(0.0, 1.0, 0.0));
+        assert_eq!(n3, Tuple::new_vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn intersect_with_parallel_ray() {
+        let plane = Plane::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 10.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert!(plane.local_intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn intersect_with_coplanar_ray() {
+        let plane = Plane::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert!(plane.local_intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn ray_intersecting_plane_from_above() {
+        let plane = Plane::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 1.0, 0.0),
+            Tuple::new_vector(0.0, -1.0, 0.0),
+        );
+        let xs = plane.local_intersect(&ray);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(std::ptr::addr_eq(xs[0].object, &plane));
+    }
+
+    #[test]
+    fn ray_intersecting_plane_from_below() {
+        let plane = Plane::new();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, -1.0, 0.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let xs = plane.local_intersect(&ray);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(std::ptr::addr_eq(xs[0].object, &plane));
+    }
+
+    #[test]
+    fn plane_has_bounding_box() {
+        let bounds = Plane::new().bounds();
+
+        assert_eq!(
+            bounds.min.get(),
+            (f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY, 1.0)
+        );
+        assert_eq!(bounds.max.get(), (f64::INFINITY, 0.0, f64::INFINITY, 1.0));
+    }
+}
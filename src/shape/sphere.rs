@@ -0,0 +1,266 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{self, Intersection},
+    material::Material,
+    ray::Ray,
+    shape::{Shape, ShapeData},
+    tuple::Tuple,
+};
+
+/// A unit sphere centered at the origin. Its size and position in the world
+/// are controlled by its transformation matrix.
+#[derive(Debug, Clone, Default)]
+pub struct Sphere {
+    data: ShapeData,
+}
+
+impl Sphere {
+    /// Creates a unit sphere with an identity transform
+    pub fn new() -> Self {
+        Self {
+            data: ShapeData::new(),
+        }
+    }
+
+    /// Creates a unit sphere made of glass: fully transparent with a
+    /// refractive index of 1.5
+    pub fn glass() -> Self {
+        let mut material = Material::new();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+
+        let mut sphere = Self::new();
+        sphere.set_material(material);
+        sphere
+    }
+}
+
+impl Shape for Sphere {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// A ray that misses returns an empty list, a tangent ray returns the
+    /// same t twice
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        let sphere_to_ray = &local_ray.origin - Tuple::new_point(0.0, 0.0, 0.0);
+
+        let a = Tuple::dot(&local_ray.direction, &local_ray.direction);
+        let b = 2.0 * Tuple::dot(&local_ray.direction, &sphere_to_ray);
+        let c = Tuple::dot(&sphere_to_ray, &sphere_to_ray) - 1.0;
+        let discriminant = b * b - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            return vec![];
+        }
+
+        let sqrt_disc = discriminant.sqrt();
+        intersection::intersections(vec![
+            Intersection::new((-b - sqrt_disc) / (2.0 * a), self),
+            Intersection::new((-b + sqrt_disc) / (2.0 * a), self),
+        ])
+    }
+
+    fn local_normal_at(&self, local_point: &Tuple) -> Tuple {
+        local_point - Tuple::new_point(0.0, 0.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::new_point(-1.0, -1.0, -1.0),
+            Tuple::new_point(1.0, 1.0, 1.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{matrix::Matrix4, transform};
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn ray_intersects_sphere_at_two_points() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let xs = sphere.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 6.0);
+    }
+
+    #[test]
+    fn ray_intersects_sphere_at_a_tangent() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 1.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let xs = sphere.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 5.0);
+        assert_eq!(xs[1].t, 5.0);
+    }
+
+    #[test]
+    fn ray_misses_sphere() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 2.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+
+        assert!(sphere.intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn ray_originates_inside_sphere() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let xs = sphere.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, -1.0);
+        assert_eq!(xs[1].t, 1.0);
+    }
+
+    #[test]
+    fn sphere_is_behind_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let xs = sphere.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, -6.0);
+        assert_eq!(xs[1].t, -4.0);
+    }
+
+    #[test]
+    fn intersect_sets_the_object() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let sphere = Sphere::new();
+        let xs = sphere.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert!(std::ptr::addr_eq(xs[0].object, &sphere));
+        assert!(std::ptr::addr_eq(xs[1].object, &sphere));
+    }
+
+    #[test]
+    fn intersecting_scaled_sphere_with_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::scaling(2.0, 2.0, 2.0));
+        let xs = sphere.intersect(&ray);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 3.0);
+        assert_eq!(xs[1].t, 7.0);
+    }
+
+    #[test]
+    fn intersecting_translated_sphere_with_ray() {
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::translation(5.0, 0.0, 0.0));
+
+        assert!(sphere.intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn normal_on_x_axis() {
+        let sphere = Sphere::new();
+        let normal = sphere.normal_at(&Tuple::new_point(1.0, 0.0, 0.0));
+        assert_eq!(normal, Tuple::new_vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn normal_on_y_axis() {
+        let sphere = Sphere::new();
+        let normal = sphere.normal_at(&Tuple::new_point(0.0, 1.0, 0.0));
+        assert_eq!(normal, Tuple::new_vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn normal_on_z_axis() {
+        let sphere = Sphere::new();
+        let normal = sphere.normal_at(&Tuple::new_point(0.0, 0.0, 1.0));
+        assert_eq!(normal, Tuple::new_vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_at_nonaxial_point() {
+        let sphere = Sphere::new();
+        let k = f64::sqrt(3.0) / 3.0;
+        let normal = sphere.normal_at(&Tuple::new_point(k, k, k));
+
+        assert_eq!(normal, Tuple::new_vector(k, k, k));
+        assert_eq!(normal, normal.normalize());
+    }
+
+    #[test]
+    fn normal_on_translated_sphere() {
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::translation(0.0, 1.0, 0.0));
+        let normal = sphere.normal_at(&Tuple::new_point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_eq!(
+            normal,
+            Tuple::new_vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    fn normal_on_transformed_sphere() {
+        let mut sphere = Sphere::new();
+        sphere.set_transform(transform::scaling(1.0, 0.5, 1.0) * transform::rotation_z(PI / 5.0));
+        let normal = sphere.normal_at(&Tuple::new_point(
+            0.0,
+            f64::sqrt(2.0) / 2.0,
+            -f64::sqrt(2.0) / 2.0,
+        ));
+
+        assert_eq!(normal, Tuple::new_vector(0.0, 0.97014, -0.24254));
+    }
+
+    #[test]
+    fn helper_for_producing_glass_sphere() {
+        let sphere = Sphere::glass();
+
+        assert_eq!(*sphere.transform(), Matrix4::identity());
+        assert_eq!(sphere.material().transparency, 1.0);
+        assert_eq!(sphere.material().refractive_index, 1.5);
+    }
+
+    #[test]
+    fn sphere_has_bounding_box() {
+        let bounds = Sphere::new().bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-1.0, -1.0, -1.0));
+        assert_eq!(bounds.max, Tuple::new_point(1.0, 1.0, 1.0));
+    }
+}
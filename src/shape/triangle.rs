@@ -0,0 +1,379 @@
+use crate::{
+    EPSILON,
+    bounds::BoundingBox,
+    intersection::Intersection,
+    ray::Ray,
+    shape::{Shape, ShapeData},
+    tuple::Tuple,
+};
+
+/// A flat triangle with corners p1, p2 and p3. The edge vectors and the
+/// normal are precomputed, so the corners can't be moved after creation.
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    data: ShapeData,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+    normal: Tuple,
+}
+
+impl Triangle {
+    /// Creates a triangle from its three corner points
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Self {
+        let e1 = &p2 - &p1;
+        let e2 = &p3 - &p1;
+        let normal = Tuple::cross(&e2, &e1).normalize();
+        Self {
+            data: ShapeData::new(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal,
+        }
+    }
+
+    pub fn p1(&self) -> &Tuple {
+        &self.p1
+    }
+
+    pub fn p2(&self) -> &Tuple {
+        &self.p2
+    }
+
+    pub fn p3(&self) -> &Tuple {
+        &self.p3
+    }
+
+    /// Returns the edge vector from p1 to p2
+    pub fn e1(&self) -> &Tuple {
+        &self.e1
+    }
+
+    /// Returns the edge vector from p1 to p3
+    pub fn e2(&self) -> &Tuple {
+        &self.e2
+    }
+
+    pub fn normal(&self) -> &Tuple {
+        &self.normal
+    }
+}
+
+impl Shape for Triangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(local_ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => vec![],
+        }
+    }
+
+    fn local_normal_at(&self, _local_point: &Tuple) -> Tuple {
+        self.normal.clone()
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        triangle_bounds(&self.p1, &self.p2, &self.p3)
+    }
+}
+
+/// A triangle whose normal is interpolated between the normals n1, n2 and
+/// n3 given at its corners, making meshes of triangles look smooth
+#[derive(Debug, Clone)]
+pub struct SmoothTriangle {
+    data: ShapeData,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    n1: Tuple,
+    n2: Tuple,
+    n3: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+}
+
+impl SmoothTriangle {
+    /// Creates a triangle from its three corner points and the normals at
+    /// each of them
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Self {
+        let e1 = &p2 - &p1;
+        let e2 = &p3 - &p1;
+        Self {
+            data: ShapeData::new(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1,
+            e2,
+        }
+    }
+
+    pub fn p1(&self) -> &Tuple {
+        &self.p1
+    }
+
+    pub fn p2(&self) -> &Tuple {
+        &self.p2
+    }
+
+    pub fn p3(&self) -> &Tuple {
+        &self.p3
+    }
+
+    pub fn n1(&self) -> &Tuple {
+        &self.n1
+    }
+
+    pub fn n2(&self) -> &Tuple {
+        &self.n2
+    }
+
+    pub fn n3(&self) -> &Tuple {
+        &self.n3
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(local_ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => vec![],
+        }
+    }
+
+    /// Without an intersection to interpolate from, falls back to the
+    /// normal of the flat triangle
+    fn local_normal_at(&self, _local_point: &Tuple) -> Tuple {
+        Tuple::cross(&self.e2, &self.e1).normalize()
+    }
+
+    fn local_normal_at_hit(&self, _local_point: &Tuple, hit: &Intersection) -> Tuple {
+        &self.n2 * hit.u + &self.n3 * hit.v + &self.n1 * (1.0 - hit.u - hit.v)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        triangle_bounds(&self.p1, &self.p2, &self.p3)
+    }
+}
+
+fn triangle_bounds(p1: &Tuple, p2: &Tuple, p3: &Tuple) -> BoundingBox {
+    let mut bounds = BoundingBox::empty();
+    for point in [p1, p2, p3] {
+        bounds.add_point(point);
+    }
+    bounds
+}
+
+/// Intersects a ray with the triangle at corner p1 with edges e1 and e2,
+/// using the Möller–Trumbore algorithm. Returns the t of the intersection
+/// along with its barycentric coordinates (u, v), if the ray hits.
+pub(crate) fn intersect_triangle(
+    ray: &Ray,
+    p1: &Tuple,
+    e1: &Tuple,
+    e2: &Tuple,
+) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = Tuple::cross(&ray.direction, e2);
+    let det = Tuple::dot(e1, &dir_cross_e2);
+
+    // the ray is parallel to the triangle's plane
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = &ray.origin - p1;
+    let u = f * Tuple::dot(&p1_to_origin, &dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = Tuple::cross(&p1_to_origin, e1);
+    let v = f * Tuple::dot(&ray.direction, &origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = f * Tuple::dot(e2, &origin_cross_e1);
+    Some((t, u, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersection;
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Tuple::new_point(0.0, 1.0, 0.0),
+            Tuple::new_point(-1.0, 0.0, 0.0),
+            Tuple::new_point(1.0, 0.0, 0.0),
+        )
+    }
+
+    fn smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Tuple::new_point(0.0, 1.0, 0.0),
+            Tuple::new_point(-1.0, 0.0, 0.0),
+            Tuple::new_point(1.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+            Tuple::new_vector(-1.0, 0.0, 0.0),
+            Tuple::new_vector(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_a_triangle() {
+        let t = triangle();
+
+        assert_eq!(*t.p1(), Tuple::new_point(0.0, 1.0, 0.0));
+        assert_eq!(*t.p2(), Tuple::new_point(-1.0, 0.0, 0.0));
+        assert_eq!(*t.p3(), Tuple::new_point(1.0, 0.0, 0.0));
+        assert_eq!(*t.e1(), Tuple::new_vector(-1.0, -1.0, 0.0));
+        assert_eq!(*t.e2(), Tuple::new_vector(1.0, -1.0, 0.0));
+        assert_eq!(*t.normal(), Tuple::new_vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_on_a_triangle() {
+        let t = triangle();
+
+        for point in [
+            Tuple::new_point(0.0, 0.5, 0.0),
+            Tuple::new_point(-0.5, 0.75, 0.0),
+            Tuple::new_point(0.5, 0.25, 0.0),
+        ] {
+            assert_eq!(t.local_normal_at(&point), *t.normal());
+        }
+    }
+
+    #[test]
+    fn intersecting_ray_parallel_to_triangle() {
+        let t = triangle();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, -1.0, -2.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+
+        assert!(t.local_intersect(&ray).is_empty());
+    }
+
+    #[test]
+    // A ray misses each of the triangle's edges
+    fn ray_misses_triangle_edges() {
+        let t = triangle();
+        let origins = [
+            // beyond the p1-p3 edge
+            Tuple::new_point(1.0, 1.0, -2.0),
+            // beyond the p1-p2 edge
+            Tuple::new_point(-1.0, 1.0, -2.0),
+            // beyond the p2-p3 edge
+            Tuple::new_point(0.0, -1.0, -2.0),
+        ];
+
+        for origin in origins {
+            let ray = Ray::new(origin, Tuple::new_vector(0.0, 0.0, 1.0));
+            assert!(t.local_intersect(&ray).is_empty());
+        }
+    }
+
+    #[test]
+    fn ray_strikes_a_triangle() {
+        let t = triangle();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.5, -2.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = t.local_intersect(&ray);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+    }
+
+    #[test]
+    fn constructing_a_smooth_triangle() {
+        let t = smooth_triangle();
+
+        assert_eq!(*t.p1(), Tuple::new_point(0.0, 1.0, 0.0));
+        assert_eq!(*t.p2(), Tuple::new_point(-1.0, 0.0, 0.0));
+        assert_eq!(*t.p3(), Tuple::new_point(1.0, 0.0, 0.0));
+        assert_eq!(*t.n1(), Tuple::new_vector(0.0, 1.0, 0.0));
+        assert_eq!(*t.n2(), Tuple::new_vector(-1.0, 0.0, 0.0));
+        assert_eq!(*t.n3(), Tuple::new_vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    // An intersection with a smooth triangle stores u/v
+    fn smooth_triangle_intersection_stores_u_v() {
+        let t = smooth_triangle();
+        let ray = Ray::new(
+            Tuple::new_point(-0.2, 0.3, -2.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = t.local_intersect(&ray);
+
+        assert!((xs[0].u - 0.45).abs() < EPSILON);
+        assert!((xs[0].v - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    // A smooth triangle uses u/v to interpolate the normal
+    fn smooth_triangle_interpolates_normal() {
+        let t = smooth_triangle();
+        let i = Intersection::with_uv(1.0, &t, 0.45, 0.25);
+        let normal = t.normal_at_hit(&Tuple::new_point(0.0, 0.0, 0.0), &i);
+
+        assert_eq!(normal, Tuple::new_vector(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn preparing_normal_on_smooth_triangle() {
+        let t = smooth_triangle();
+        let i = Intersection::with_uv(1.0, &t, 0.45, 0.25);
+        let ray = Ray::new(
+            Tuple::new_point(-0.2, 0.3, -2.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = intersection::intersections(vec![i]);
+        let comps = i.prepare_computations(&ray, &xs);
+
+        assert_eq!(comps.normalv, Tuple::new_vector(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn triangle_has_bounding_box() {
+        let t = Triangle::new(
+            Tuple::new_point(-3.0, 7.0, 2.0),
+            Tuple::new_point(6.0, 2.0, -4.0),
+            Tuple::new_point(2.0, -1.0, -1.0),
+        );
+        let bounds = t.bounds();
+
+        assert_eq!(bounds.min, Tuple::new_point(-3.0, -1.0, -4.0));
+        assert_eq!(bounds.max, Tuple::new_point(6.0, 7.0, 2.0));
+        assert_eq!(smooth_triangle().bounds(), triangle().bounds());
+    }
+}
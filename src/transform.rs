@@ -46,7 +46,7 @@ pub fn rotation_z(rad: f64) -> Matrix4 {
     Matrix4::from_tuples_by_row(
         Tuple::new(rad.cos(), -rad.sin(), 0., 0.),
         Tuple::new(rad.sin(), rad.cos(), 0., 0.),
-        Tuple::new(0., 0., 0., 0.),
+        Tuple::new(0., 0., 1., 0.),
         Tuple::new(0., 0., 0., 1.),
     )
 }
@@ -64,9 +64,32 @@ pub fn shear(x_y: f64, x_z: f64, y_x: f64, y_z: f64, z_x: f64, z_y: f64) -> Matr
     )
 }
 
+/// Creates a view transformation matrix which orients the world relative
+/// to an eye positioned at `from`, looking at the point `to`, with `up`
+/// being the vector (approximately) pointing upwards
+pub fn view_transform(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix4 {
+    let forward = (to - from).normalize();
+    let left = Tuple::cross(&forward, &up.normalize());
+    let true_up = Tuple::cross(&left, &forward);
+
+    let (left_x, left_y, left_z, _) = left.get();
+    let (up_x, up_y, up_z, _) = true_up.get();
+    let (forward_x, forward_y, forward_z, _) = forward.get();
+    let (from_x, from_y, from_z, _) = from.get();
+
+    let orientation = Matrix4::from_tuples_by_row(
+        Tuple::new(left_x, left_y, left_z, 0.),
+        Tuple::new(up_x, up_y, up_z, 0.),
+        Tuple::new(-forward_x, -forward_y, -forward_z, 0.),
+        Tuple::new(0., 0., 0., 1.),
+    );
+    orientation * translation(-from_x, -from_y, -from_z)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
+        matrix::Matrix4,
         transform::{self},
         tuple::Tuple,
     };
@@ -167,6 +190,15 @@ mod tests {
         assert_eq!(full_quarter * point_p, Tuple::new_point(-1., 0., 0.));
     }
 
+    #[test]
+    fn rotating_z_axis_preserves_z() {
+        let point_p = Tuple::new_point(0., 1., 1.);
+        let full_quarter = transform::rotation_z(consts::PI / 2.0);
+
+        assert!(full_quarter.invertible());
+        assert_eq!(full_quarter * point_p, Tuple::new_point(-1., 0., 1.));
+    }
+
     #[test]
     fn shear_transform() {
         // A shearing transformation moves x in proportion to y
@@ -229,4 +261,53 @@ mod tests {
         let matrix = matrix_t * matrix_s * matrix_r;
         assert_eq!(matrix * point_p, Tuple::new_point(15., 0., 7.));
     }
+
+    #[test]
+    // The transformation matrix for the default orientation
+    fn view_transform_default_orientation() {
+        let from = Tuple::new_point(0., 0., 0.);
+        let to = Tuple::new_point(0., 0., -1.);
+        let up = Tuple::new_vector(0., 1., 0.);
+
+        assert_eq!(transform::view_transform(&from, &to, &up), Matrix4::identity());
+    }
+
+    #[test]
+    // A view transformation matrix looking in positive z direction
+    fn view_transform_looking_positive_z() {
+        let from = Tuple::new_point(0., 0., 0.);
+        let to = Tuple::new_point(0., 0., 1.);
+        let up = Tuple::new_vector(0., 1., 0.);
+
+        assert_eq!(
+            transform::view_transform(&from, &to, &up),
+            transform::scaling(-1., 1., -1.)
+        );
+    }
+
+    #[test]
+    // The view transformation moves the world
+    fn view_transform_moves_the_world() {
+        let from = Tuple::new_point(0., 0., 8.);
+        let to = Tuple::new_point(0., 0., 0.);
+        let up = Tuple::new_vector(0., 1., 0.);
+
+        assert_eq!(
+            transform::view_transform(&from, &to, &up),
+            transform::translation(0., 0., -8.)
+        );
+    }
+
+    #[test]
+    fn arbitrary_view_transformation() {
+        let from = Tuple::new_point(1., 3., 2.);
+        let to = Tuple::new_point(4., -2., 8.);
+        let up = Tuple::new_vector(1., 1., 0.);
+        let result = Matrix4::from_array([
+            -0.50709, 0.50709, 0.67612, -2.36643, 0.76772, 0.60609, 0.12122, -2.82843, -0.35857,
+            0.59761, -0.71714, 0.00000, 0.00000, 0.00000, 0.00000, 1.00000,
+        ]);
+
+        assert_eq!(transform::view_transform(&from, &to, &up), result);
+    }
 }
@@ -162,6 +162,14 @@ impl Tuple {
             vector_a.x * vector_b.y - vector_a.y * vector_b.x,
         )
     }
+
+    /// Reflects the vector around a (normalized) normal vector
+    /// ```text
+    /// reflect(v, n) = v - n * 2 * dot(v, n)
+    /// ```
+    pub fn reflect(&self, normal: &Tuple) -> Tuple {
+        self - normal * (2.0 * Tuple::dot(self, normal))
+    }
 }
 
 impl ops::Add for Tuple {
@@ -182,7 +190,7 @@ impl ops::Add<Tuple> for &Tuple {
         Tuple::add(self, &rhs)
     }
 }
-impl<'a, 'b> ops::Add<&'b Tuple> for &'a Tuple {
+impl<'b> ops::Add<&'b Tuple> for &Tuple {
     type Output = Tuple;
     fn add(self, rhs: &'b Tuple) -> Self::Output {
         Tuple::add(self, rhs)
@@ -209,7 +217,7 @@ impl ops::Sub<Tuple> for &Tuple {
         Tuple::sub(self, &rhs)
     }
 }
-impl<'a, 'b> ops::Sub<&'b Tuple> for &'a Tuple {
+impl<'b> ops::Sub<&'b Tuple> for &Tuple {
     type Output = Tuple;
 
     fn sub(self, rhs: &'b Tuple) -> Self::Output {
@@ -408,4 +416,22 @@ mod tests {
             Tuple::new_vector(1.0, -2.0, 1.0)
         );
     }
+
+    #[test]
+    // Reflecting a vector approaching at 45°
+    fn reflecting_vector_at_45_degrees() {
+        let vector = Tuple::new_vector(1.0, -1.0, 0.0);
+        let normal = Tuple::new_vector(0.0, 1.0, 0.0);
+
+        assert_eq!(vector.reflect(&normal), Tuple::new_vector(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    // Reflecting a vector off a slanted surface
+    fn reflecting_vector_off_slanted_surface() {
+        let vector = Tuple::new_vector(0.0, -1.0, 0.0);
+        let normal = Tuple::new_vector(f64::sqrt(2.0) / 2.0, f64::sqrt(2.0) / 2.0, 0.0);
+
+        assert_eq!(vector.reflect(&normal), Tuple::new_vector(1.0, 0.0, 0.0));
+    }
 }
@@ -0,0 +1,657 @@
+use crate::{
+    draw::Color,
+    intersection::{self, Computations, Intersection},
+    light::{self, PointLight},
+    ray::Ray,
+    shape::Shape,
+    tuple::Tuple,
+};
+
+/// Default limit on how many times a ray may bounce between reflective and
+/// refractive surfaces
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+
+/// A collection of objects and light sources making up a scene
+#[derive(Debug)]
+pub struct World {
+    pub objects: Vec<Box<dyn Shape>>,
+    pub lights: Vec<PointLight>,
+    /// Maximum number of recursive reflection and refraction rays spawned
+    /// from a single camera ray. Prevents infinite recursion between
+    /// facing mirrors.
+    pub max_depth: usize,
+}
+
+impl World {
+    /// Creates an empty world with no objects and no light sources
+    pub fn new() -> Self {
+        Self {
+            objects: vec![],
+            lights: vec![],
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Intersects a ray with every object in the world, returning all
+    /// intersections sorted by t
+    pub fn intersect_world(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let xs = self
+            .objects
+            .iter()
+            .flat_map(|object| object.intersect(ray))
+            .collect();
+        intersection::intersections(xs)
+    }
+
+    /// Computes the color at a precomputed hit, summing the contribution of
+    /// every light source in the world with the reflected and refracted
+    /// colors. `remaining` is the number of recursive rays still allowed.
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        let surface = self
+            .lights
+            .iter()
+            .map(|light| {
+                let in_shadow = self.is_shadowed(light, &comps.over_point);
+                light::lighting(
+                    comps.object.material(),
+                    comps.object,
+                    light,
+                    &comps.over_point,
+                    &comps.eyev,
+                    &comps.normalv,
+                    in_shadow,
+                )
+            })
+            .fold(Color::new(0.0, 0.0, 0.0), |acc, color| acc + color);
+
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+
+        let material = comps.object.material();
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            // blend reflection and refraction using the Fresnel effect
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    /// Computes the color reflected by the surface at the hit, which is
+    /// black for non-reflective surfaces or when the recursion limit is
+    /// reached
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object.material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+
+        let reflect_ray = Ray::new(comps.over_point.clone(), comps.reflectv.clone());
+        self.color_at_depth(&reflect_ray, remaining - 1) * reflective
+    }
+
+    /// Computes the color seen through the surface at the hit, which is
+    /// black for opaque surfaces, under total internal reflection, or when
+    /// the recursion limit is reached
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object.material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+
+        // Snell's law: sin(theta_i) / sin(theta_t) = n2 / n1
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = Tuple::dot(&comps.eyev, &comps.normalv);
+        let sin2_t = n_ratio * n_ratio * (1.0 - cos_i * cos_i);
+        if sin2_t > 1.0 {
+            // total internal reflection
+            return Color::new(0.0, 0.0, 0.0);
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = &comps.normalv * (n_ratio * cos_i - cos_t) - &comps.eyev * n_ratio;
+        let refract_ray = Ray::new(comps.under_point.clone(), direction);
+
+        self.color_at_depth(&refract_ray, remaining - 1) * transparency
+    }
+
+    /// Checks whether the point is hidden from the light by an object.
+    /// Lights that don't cast shadows never shadow a point, and objects that
+    /// don't cast shadows are ignored by the test.
+    pub fn is_shadowed(&self, light: &PointLight, point: &Tuple) -> bool {
+        if !light.casts_shadows {
+            return false;
+        }
+
+        let v = &light.position - point;
+        let distance = v.magnitude();
+        let ray = Ray::new(point.clone(), v.normalize());
+
+        self.objects
+            .iter()
+            .filter(|object| object.casts_shadows())
+            .any(|object| object.occludes(&ray, distance))
+    }
+
+    /// Computes the color seen along a ray, which is black when the ray
+    /// hits nothing. Recursion is limited to `max_depth` bounces.
+    pub fn color_at(&self, ray: &Ray) -> Color {
+        self.color_at_depth(ray, self.max_depth)
+    }
+
+    /// Computes the color seen along a ray, allowing at most `remaining`
+    /// recursive reflection and refraction rays
+    pub fn color_at_depth(&self, ray: &Ray, remaining: usize) -> Color {
+        let xs = self.intersect_world(ray);
+        match intersection::hit(&xs) {
+            Some(hit) => self.shade_hit(&hit.prepare_computations(ray, &xs), remaining),
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The default test world: a light at (-10, 10, -10) and two concentric
+/// spheres, the outer one a unit sphere and the inner one scaled by half
+#[cfg(test)]
+pub(crate) fn default_world() -> World {
+    use crate::{material::Material, shape::Sphere, transform, tuple::Tuple};
+
+    let light = PointLight::new(
+        Tuple::new_point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    );
+
+    let mut outer = Sphere::new();
+    let mut material = Material::new();
+    material.color = Color::new(0.8, 1.0, 0.6);
+    material.diffuse = 0.7;
+    material.specular = 0.2;
+    outer.set_material(material);
+
+    let mut inner = Sphere::new();
+    inner.set_transform(transform::scaling(0.5, 0.5, 0.5));
+
+    World {
+        objects: vec![Box::new(outer), Box::new(inner)],
+        lights: vec![light],
+        max_depth: DEFAULT_MAX_DEPTH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        EPSILON,
+        material::Material,
+        pattern::tests::TestPattern,
+        shape::{Plane, Sphere},
+        transform,
+        tuple::Tuple,
+    };
+    use std::f64::consts::FRAC_1_SQRT_2;
+    use std::sync::Arc;
+
+    #[test]
+    fn creating_a_world() {
+        let world = World::new();
+
+        assert!(world.objects.is_empty());
+        assert!(world.lights.is_empty());
+    }
+
+    #[test]
+    fn intersect_world_with_ray() {
+        let world = default_world();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = world.intersect_world(&ray);
+
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 4.5);
+        assert_eq!(xs[2].t, 5.5);
+        assert_eq!(xs[3].t, 6.0);
+    }
+
+    #[test]
+    fn shading_an_intersection() {
+        let world = default_world();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let shape = world.objects[0].as_ref();
+        let i = Intersection::new(4.0, shape);
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            world.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.38066, 0.47583, 0.2855)
+        );
+    }
+
+    #[test]
+    fn shading_an_intersection_from_the_inside() {
+        let mut world = default_world();
+        world.lights = vec![PointLight::new(
+            Tuple::new_point(0.0, 0.25, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        )];
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let shape = world.objects[1].as_ref();
+        let i = Intersection::new(0.5, shape);
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            world.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.90498, 0.90498, 0.90498)
+        );
+    }
+
+    #[test]
+    fn color_when_ray_misses() {
+        let world = default_world();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+
+        assert_eq!(world.color_at(&ray), Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn color_when_ray_hits() {
+        let world = default_world();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+
+        assert_eq!(world.color_at(&ray), Color::new(0.38066, 0.47583, 0.2855));
+    }
+
+    #[test]
+    fn color_with_intersection_behind_ray() {
+        let mut world = default_world();
+        for object in world.objects.iter_mut() {
+            let mut material = object.material().clone();
+            material.ambient = 1.0;
+            object.set_material(material);
+        }
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.75),
+            Tuple::new_vector(0.0, 0.0, -1.0),
+        );
+
+        assert_eq!(world.color_at(&ray), world.objects[1].material().color);
+    }
+
+    #[test]
+    fn shading_sums_contribution_of_every_light() {
+        let mut world = default_world();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let single = world.color_at(&ray);
+        world.lights.push(world.lights[0].clone());
+
+        assert_eq!(world.color_at(&ray), single * 2.0);
+    }
+
+    #[test]
+    // There is no shadow when nothing is collinear with point and light
+    fn no_shadow_when_nothing_collinear() {
+        let world = default_world();
+        let point = Tuple::new_point(0.0, 10.0, 0.0);
+
+        assert!(!world.is_shadowed(&world.lights[0], &point));
+    }
+
+    #[test]
+    // The shadow when an object is between the point and the light
+    fn shadow_when_object_between_point_and_light() {
+        let world = default_world();
+        let point = Tuple::new_point(10.0, -10.0, 10.0);
+
+        assert!(world.is_shadowed(&world.lights[0], &point));
+    }
+
+    #[test]
+    // There is no shadow when an object is behind the light
+    fn no_shadow_when_object_behind_light() {
+        let world = default_world();
+        let point = Tuple::new_point(-20.0, 20.0, -20.0);
+
+        assert!(!world.is_shadowed(&world.lights[0], &point));
+    }
+
+    #[test]
+    // There is no shadow when an object is behind the point
+    fn no_shadow_when_object_behind_point() {
+        let world = default_world();
+        let point = Tuple::new_point(-2.0, 2.0, -2.0);
+
+        assert!(!world.is_shadowed(&world.lights[0], &point));
+    }
+
+    #[test]
+    fn no_shadow_from_light_that_does_not_cast_shadows() {
+        let mut world = default_world();
+        world.lights[0].casts_shadows = false;
+        let point = Tuple::new_point(10.0, -10.0, 10.0);
+
+        assert!(!world.is_shadowed(&world.lights[0], &point));
+    }
+
+    #[test]
+    fn no_shadow_from_object_that_does_not_cast_shadows() {
+        let mut world = default_world();
+        for object in world.objects.iter_mut() {
+            object.set_casts_shadows(false);
+        }
+        let point = Tuple::new_point(10.0, -10.0, 10.0);
+
+        assert!(!world.is_shadowed(&world.lights[0], &point));
+    }
+
+    #[test]
+    fn shade_hit_is_given_an_intersection_in_shadow() {
+        let mut world = World::new();
+        world.lights.push(PointLight::new(
+            Tuple::new_point(0.0, 0.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        ));
+        let mut s2 = Sphere::new();
+        s2.set_transform(transform::translation(0.0, 0.0, 10.0));
+        world.objects = vec![Box::new(Sphere::new()), Box::new(s2)];
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let i = Intersection::new(4.0, world.objects[1].as_ref());
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            world.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn reflected_color_for_nonreflective_material() {
+        let mut world = default_world();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let mut material = world.objects[1].material().clone();
+        material.ambient = 1.0;
+        world.objects[1].set_material(material);
+        let i = Intersection::new(1.0, world.objects[1].as_ref());
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            world.reflected_color(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    /// Adds a reflective plane below the default world's spheres
+    fn world_with_reflective_plane() -> World {
+        let mut world = default_world();
+        let mut shape = Plane::new();
+        let mut material = Material::new();
+        material.reflective = 0.5;
+        shape.set_material(material);
+        shape.set_transform(transform::translation(0.0, -1.0, 0.0));
+        world.objects.push(Box::new(shape));
+        world
+    }
+
+    #[test]
+    fn reflected_color_for_reflective_material() {
+        let world = world_with_reflective_plane();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -3.0),
+            Tuple::new_vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(f64::sqrt(2.0), world.objects[2].as_ref());
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            world.reflected_color(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.19033, 0.23791, 0.14274)
+        );
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_material() {
+        let world = world_with_reflective_plane();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -3.0),
+            Tuple::new_vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(f64::sqrt(2.0), world.objects[2].as_ref());
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(
+            world.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.87676, 0.92434, 0.82917)
+        );
+    }
+
+    #[test]
+    // color_at with mutually reflective surfaces terminates
+    fn color_at_with_mutually_reflective_surfaces() {
+        let mut world = World::new();
+        world.lights.push(PointLight::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        ));
+        let mut material = Material::new();
+        material.reflective = 1.0;
+
+        let mut lower = Plane::new();
+        lower.set_material(material.clone());
+        lower.set_transform(transform::translation(0.0, -1.0, 0.0));
+
+        let mut upper = Plane::new();
+        upper.set_material(material);
+        upper.set_transform(transform::translation(0.0, 1.0, 0.0));
+
+        world.objects = vec![Box::new(lower), Box::new(upper)];
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.0),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+
+        world.color_at(&ray);
+    }
+
+    #[test]
+    // The reflected color at the maximum recursive depth
+    fn reflected_color_at_maximum_recursive_depth() {
+        let world = world_with_reflective_plane();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -3.0),
+            Tuple::new_vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(f64::sqrt(2.0), world.objects[2].as_ref());
+        let comps = i.prepare_computations(&ray, &[i]);
+
+        assert_eq!(world.reflected_color(&comps, 0), Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_with_opaque_surface() {
+        let world = default_world();
+        let shape = world.objects[0].as_ref();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = [Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        assert_eq!(
+            world.refracted_color(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn refracted_color_at_maximum_recursive_depth() {
+        let mut world = default_world();
+        let mut material = world.objects[0].material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        world.objects[0].set_material(material);
+        let shape = world.objects[0].as_ref();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -5.0),
+            Tuple::new_vector(0.0, 0.0, 1.0),
+        );
+        let xs = [Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        assert_eq!(world.refracted_color(&comps, 0), Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let mut world = default_world();
+        let mut material = world.objects[0].material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        world.objects[0].set_material(material);
+        let shape = world.objects[0].as_ref();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, FRAC_1_SQRT_2),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let xs = [
+            Intersection::new(-FRAC_1_SQRT_2, shape),
+            Intersection::new(FRAC_1_SQRT_2, shape),
+        ];
+        // inside the sphere, so look at the second intersection
+        let comps = xs[1].prepare_computations(&ray, &xs);
+
+        assert_eq!(
+            world.refracted_color(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn refracted_color_with_refracted_ray() {
+        let mut world = default_world();
+        let mut material = world.objects[0].material().clone();
+        material.ambient = 1.0;
+        material.pattern = Some(Arc::new(TestPattern::new()));
+        world.objects[0].set_material(material);
+
+        let mut material = world.objects[1].material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        world.objects[1].set_material(material);
+
+        let a = world.objects[0].as_ref();
+        let b = world.objects[1].as_ref();
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, 0.1),
+            Tuple::new_vector(0.0, 1.0, 0.0),
+        );
+        let xs = [
+            Intersection::new(-0.9899, a),
+            Intersection::new(-0.4899, b),
+            Intersection::new(0.4899, b),
+            Intersection::new(0.9899, a),
+        ];
+        let comps = xs[2].prepare_computations(&ray, &xs);
+        let color = world.refracted_color(&comps, DEFAULT_MAX_DEPTH);
+
+        assert!((color.red - 0.0).abs() < EPSILON);
+        assert!((color.green - 0.99888).abs() < 1e-4);
+        assert!((color.blue - 0.04725).abs() < 1e-4);
+    }
+
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        let mut world = default_world();
+        let mut floor = Plane::new();
+        floor.set_transform(transform::translation(0.0, -1.0, 0.0));
+        let mut material = Material::new();
+        material.transparency = 0.5;
+        material.refractive_index = 1.5;
+        floor.set_material(material);
+
+        let mut ball = Sphere::new();
+        let mut material = Material::new();
+        material.color = Color::new(1.0, 0.0, 0.0);
+        material.ambient = 0.5;
+        ball.set_material(material);
+        ball.set_transform(transform::translation(0.0, -3.5, -0.5));
+
+        world.objects.push(Box::new(floor));
+        world.objects.push(Box::new(ball));
+
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -3.0),
+            Tuple::new_vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let xs = [Intersection::new(f64::sqrt(2.0), world.objects[2].as_ref())];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        assert_eq!(
+            world.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.93642, 0.68642, 0.68642)
+        );
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_transparent_material() {
+        let mut world = default_world();
+        let mut floor = Plane::new();
+        floor.set_transform(transform::translation(0.0, -1.0, 0.0));
+        let mut material = Material::new();
+        material.reflective = 0.5;
+        material.transparency = 0.5;
+        material.refractive_index = 1.5;
+        floor.set_material(material);
+
+        let mut ball = Sphere::new();
+        let mut material = Material::new();
+        material.color = Color::new(1.0, 0.0, 0.0);
+        material.ambient = 0.5;
+        ball.set_material(material);
+        ball.set_transform(transform::translation(0.0, -3.5, -0.5));
+
+        world.objects.push(Box::new(floor));
+        world.objects.push(Box::new(ball));
+
+        let ray = Ray::new(
+            Tuple::new_point(0.0, 0.0, -3.0),
+            Tuple::new_vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let xs = [Intersection::new(f64::sqrt(2.0), world.objects[2].as_ref())];
+        let comps = xs[0].prepare_computations(&ray, &xs);
+
+        assert_eq!(
+            world.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.93391, 0.69643, 0.69243)
+        );
+    }
+}